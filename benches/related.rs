@@ -0,0 +1,110 @@
+// benches/related.rs
+//
+// Related-post lookups and incremental indexing over a generated blog of
+// POSTS posts, to check the term index holds up at that size.
+
+use app::database::Database;
+use app::models::Post;
+use app::post::PostManager;
+use app::related::RelatedManager;
+use criterion::{criterion_group, criterion_main, Criterion};
+use rusqlite::params;
+
+const POSTS: i64 = 20_000;
+const AUTHORS: i64 = 200;
+const VOCABULARY: usize = 8_000;
+
+/// A small deterministic generator, so every run benchmarks the same blog
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    /// Roughly Zipf-distributed word index: a few words are very common
+    fn word(&mut self) -> usize {
+        let uniform = (self.next() % 1_000_000) as f64 / 1_000_000.0;
+        ((VOCABULARY as f64).powf(uniform) as usize).saturating_sub(1)
+    }
+}
+
+fn text(rng: &mut Lcg, words: usize) -> String {
+    (0..words).map(|_| format!("term{}", rng.word())).collect::<Vec<_>>().join(" ")
+}
+
+fn build_blog() -> Database {
+    let db = Database::new(":memory:").expect("in-memory database");
+    let mut rng = Lcg(42);
+    {
+        let conn_arc = db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction().unwrap();
+        for author in 1..=AUTHORS {
+            tx.execute(
+                "INSERT INTO users (id, username, login_name, ssh_key, created_at)
+                 VALUES (?1, ?2, ?2, 'ssh-ed25519 AAAA', '2025-01-01T00:00:00+00:00')",
+                params![author, format!("author{}", author)],
+            )
+            .unwrap();
+        }
+        for id in 1..=POSTS {
+            let created_at = format!("2025-{:02}-{:02}T12:00:00+00:00", id % 12 + 1, id % 28 + 1);
+            let words = 80 + (rng.next() % 400) as usize;
+            tx.execute(
+                "INSERT INTO posts (id, user_id, title, content, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+                params![id, id % AUTHORS + 1, text(&mut rng, 6), text(&mut rng, words), created_at],
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+        RelatedManager::catch_up(&conn).unwrap();
+    }
+    db
+}
+
+fn related(c: &mut Criterion) {
+    let db = build_blog();
+    let posts = PostManager::new(db.clone());
+    let related = RelatedManager::new(db.clone());
+    let sample: Vec<Post> = (1..=50).map(|i| posts.get_post(i * (POSTS / 50)).unwrap().unwrap()).collect();
+
+    let mut group = c.benchmark_group(format!("related ({} posts)", POSTS));
+    group.sample_size(20);
+
+    let mut next = 0;
+    group.bench_function("five related posts", |b| {
+        b.iter(|| {
+            let post = &sample[next % sample.len()];
+            next += 1;
+            related.get_related(post, 1, 5).unwrap()
+        })
+    });
+
+    let mut rng = Lcg(7);
+    group.bench_function("reindex one post", |b| {
+        b.iter(|| {
+            let conn_arc = db.get_connection();
+            let conn = conn_arc.lock().unwrap();
+            let tx = conn.unchecked_transaction().unwrap();
+            RelatedManager::index_post(&tx, POSTS / 2, &text(&mut rng, 6), &text(&mut rng, 300)).unwrap();
+            tx.commit().unwrap();
+        })
+    });
+
+    // Every login runs this, and on an indexed blog it finds nothing to do
+    group.bench_function("catch up with nothing to index", |b| {
+        b.iter(|| {
+            let conn_arc = db.get_connection();
+            let conn = conn_arc.lock().unwrap();
+            RelatedManager::catch_up(&conn).unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, related);
+criterion_main!(benches);
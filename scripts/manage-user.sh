@@ -3,6 +3,8 @@
 
 set -e
 
+# USERNAME is the system login name. It is fixed at registration and does not
+# follow blog username changes, so renames never touch the system account.
 ACTION=$1
 USERNAME=$2
 SSH_KEY=$3
@@ -30,8 +30,8 @@ while true; do
         continue
     fi
     
-    # Check if username already exists in database
-    if docker exec "$CONTAINER_NAME" sqlite3 "$DB_PATH" "SELECT username FROM users WHERE username='$USERNAME';" 2>/dev/null | grep -q "$USERNAME"; then
+    # Check if username already exists in database, as a current, former or login name
+    if docker exec "$CONTAINER_NAME" sqlite3 "$DB_PATH" "SELECT username FROM users WHERE username='$USERNAME' OR login_name='$USERNAME' UNION SELECT alias FROM username_aliases WHERE alias='$USERNAME';" 2>/dev/null | grep -q "$USERNAME"; then
         echo "Username '$USERNAME' is already taken. Please choose another."
         continue
     fi
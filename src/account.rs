@@ -0,0 +1,336 @@
+// src/account.rs
+
+use crate::models::{Bookmark, Comment, GroupRole, Notification, PageRequest, Poll, Post, SavedSearch, User, UserBlock};
+use crate::database::Database;
+use crate::user::UserManager;
+use crate::post::PostManager;
+use crate::bookmark::BookmarkManager;
+use crate::block::BlockManager;
+use crate::comment::CommentManager;
+use crate::group::GroupManager;
+use crate::notification::NotificationManager;
+use crate::poll::PollManager;
+use crate::saved_search::SavedSearchManager;
+use crate::theme::Icon;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+use std::io::Write;
+
+/// Everything tied to one account, as written to `account.json`
+#[derive(Debug, Serialize)]
+pub struct AccountExport {
+    pub exported_at: DateTime<Utc>,
+    pub profile: User,
+    pub former_usernames: Vec<String>,
+    pub groups: Vec<ExportedMembership>,
+    pub posts: Vec<ExportedPost>,
+    pub comments: Vec<Comment>,
+    pub poll_votes: Vec<ExportedVote>,
+    pub post_views: Vec<ExportedView>,
+    pub bookmarks: Vec<Bookmark>,
+    pub blocks: Vec<UserBlock>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub notifications: Vec<Notification>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedMembership {
+    pub slug: String,
+    pub name: String,
+    pub role: GroupRole,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedPost {
+    #[serde(flatten)]
+    pub post: Post,
+    pub poll: Option<Poll>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedVote {
+    pub post_id: i64,
+    pub question: String,
+    pub option: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A post the user opened, from the reading history kept for ranking
+#[derive(Debug, Serialize)]
+pub struct ExportedView {
+    pub post_id: i64,
+    pub title: String,
+    pub viewed_at: DateTime<Utc>,
+}
+
+/// Self-service export and deletion. Deletion follows one policy:
+///
+/// - everything the user wrote or owns is erased: profile, keys, former
+///   usernames, posts (with their polls, comments and bookmarks), comments,
+///   votes, reading history, bookmarks, blocks, saved searches,
+///   notifications and reports they filed;
+/// - reports about the user, their posts or their comments are closed by
+///   removing them;
+/// - records other people rely on are kept but detached: moderation history
+///   loses the admin's name, reshares of erased posts show the original as
+///   unavailable, and groups they owned alone pass to the next member in line
+///   (or are removed when nobody else is left).
+pub struct AccountManager {
+    db: Database,
+}
+
+impl AccountManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub fn export(&self, user: &User) -> Result<AccountExport> {
+        let user_id = user.id.ok_or_else(|| Error::msg("User has not been saved"))?;
+        let poll_manager = PollManager::new(self.db.clone());
+
+        let post_manager = PostManager::new(self.db.clone());
+        let mut posts = Vec::new();
+        let mut request = PageRequest::First;
+        loop {
+            let page = post_manager.get_user_posts(user_id, request, 100)?;
+            for post in page.items {
+                let poll = poll_manager.get_poll_for_post(post.id.unwrap())?;
+                posts.push(ExportedPost { post, poll });
+            }
+            match page.next {
+                Some(cursor) => request = PageRequest::After(cursor),
+                None => break,
+            }
+        }
+
+        let groups = GroupManager::new(self.db.clone())
+            .get_user_groups(user_id)?
+            .into_iter()
+            .map(|(group, role)| ExportedMembership { slug: group.slug, name: group.name, role })
+            .collect();
+
+        Ok(AccountExport {
+            exported_at: Utc::now(),
+            profile: user.clone(),
+            former_usernames: UserManager::new(self.db.clone()).get_aliases(user_id)?,
+            groups,
+            posts,
+            comments: CommentManager::new(self.db.clone()).get_user_comments(user_id)?,
+            poll_votes: self.get_poll_votes(user_id)?,
+            post_views: self.get_post_views(user_id)?,
+            bookmarks: BookmarkManager::new(self.db.clone()).get_user_bookmarks(user_id)?,
+            blocks: BlockManager::new(self.db.clone()).get_user_blocks(user_id)?,
+            saved_searches: SavedSearchManager::new(self.db.clone()).get_saved_searches(user_id)?,
+            notifications: NotificationManager::new(self.db.clone()).get_notifications(user_id, i64::MAX)?,
+        })
+    }
+
+    fn get_poll_votes(&self, user_id: i64) -> Result<Vec<ExportedVote>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT p.post_id, p.question, o.label, v.created_at
+             FROM poll_votes v
+             JOIN polls p ON v.poll_id = p.id
+             JOIN poll_options o ON v.option_id = o.id
+             WHERE v.user_id = ?1
+             ORDER BY v.created_at ASC, o.position ASC"
+        )?;
+
+        let rows = stmt.query_map(params![user_id], |row| {
+            Ok(ExportedVote {
+                post_id: row.get(0)?,
+                question: row.get(1)?,
+                option: row.get(2)?,
+                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for vote in rows {
+            result.push(vote?);
+        }
+        Ok(result)
+    }
+
+    fn get_post_views(&self, user_id: i64) -> Result<Vec<ExportedView>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT v.post_id, p.title, v.viewed_at
+             FROM post_views v
+             JOIN posts p ON v.post_id = p.id
+             WHERE v.user_id = ?1
+             ORDER BY v.viewed_at ASC"
+        )?;
+
+        let rows = stmt.query_map(params![user_id], |row| {
+            Ok(ExportedView {
+                post_id: row.get(0)?,
+                title: row.get(1)?,
+                viewed_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for view in rows {
+            result.push(view?);
+        }
+        Ok(result)
+    }
+
+    /// Write the export as a tar archive: `account.json` with everything, plus
+    /// one Markdown file per post and a `comments.md` for reading by eye
+    pub fn write_archive<W: Write>(&self, export: &AccountExport, out: W) -> Result<()> {
+        let mut archive = tar::Builder::new(out);
+        let mtime = export.exported_at.timestamp().max(0) as u64;
+
+        let json = serde_json::to_string_pretty(export)?;
+        append_file(&mut archive, "account.json", json.as_bytes(), mtime)?;
+
+        for exported in &export.posts {
+            let post = &exported.post;
+            let name = format!("posts/{:05}-{}.md", post.id.unwrap_or(0), slugify(&post.title));
+            append_file(&mut archive, &name, post_markdown(exported).as_bytes(), mtime)?;
+        }
+
+        let mut comments = String::from("# Comments\n");
+        for comment in &export.comments {
+            comments.push_str(&format!(
+                "\n## On post {} {} {}\n\n{}\n",
+                comment.post_id,
+                Icon::Separator,
+                comment.created_at.format("%Y-%m-%d %H:%M UTC"),
+                comment.content
+            ));
+        }
+        append_file(&mut archive, "comments.md", comments.as_bytes(), mtime)?;
+
+        archive.into_inner()?.flush()?;
+        Ok(())
+    }
+
+    /// Erase the account according to the policy above, in one transaction
+    pub fn delete_account(&self, user_id: i64) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        Self::hand_over_groups(&tx, user_id)?;
+
+        tx.execute(
+            "DELETE FROM reports
+             WHERE (target_type = 'user' AND target_id = ?1)
+                OR (target_type = 'post' AND target_id IN (SELECT id FROM posts WHERE user_id = ?1))
+                OR (target_type = 'comment' AND target_id IN (
+                       SELECT c.id FROM comments c JOIN posts p ON p.id = c.post_id
+                       WHERE c.user_id = ?1 OR p.user_id = ?1
+                   ))",
+            params![user_id],
+        )?;
+        // Cascades to the posts' polls, comments, bookmarks and subscriptions
+        tx.execute("DELETE FROM posts WHERE user_id = ?1", params![user_id])?;
+        // Cascades to everything else keyed on the user
+        let deleted = tx.execute("DELETE FROM users WHERE id = ?1", params![user_id])?;
+        if deleted == 0 {
+            return Err(Error::msg("That account no longer exists"));
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Groups where the user is the only owner get a new one: the longest
+    /// serving editor, else the longest serving member. Empty groups go.
+    fn hand_over_groups(conn: &Connection, user_id: i64) -> Result<()> {
+        let mut stmt = conn.prepare(
+            "SELECT m.group_id FROM group_members m
+             WHERE m.user_id = ?1 AND m.role = 'owner'
+               AND NOT EXISTS (
+                   SELECT 1 FROM group_members o
+                   WHERE o.group_id = m.group_id AND o.role = 'owner' AND o.user_id != ?1
+               )"
+        )?;
+        let group_ids: Vec<i64> = stmt
+            .query_map(params![user_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+
+        for group_id in group_ids {
+            let promoted = conn.execute(
+                "UPDATE group_members SET role = 'owner'
+                 WHERE group_id = ?1 AND user_id = (
+                     SELECT user_id FROM group_members
+                     WHERE group_id = ?1 AND user_id != ?2
+                     ORDER BY role = 'editor' DESC, joined_at ASC
+                     LIMIT 1
+                 )",
+                params![group_id, user_id],
+            )?;
+            if promoted == 0 {
+                conn.execute("DELETE FROM blog_groups WHERE id = ?1", params![group_id])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn append_file<W: Write>(archive: &mut tar::Builder<W>, path: &str, data: &[u8], mtime: u64) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    header.set_cksum();
+    archive.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+fn post_markdown(exported: &ExportedPost) -> String {
+    let post = &exported.post;
+    let mut markdown = format!("# {}\n\n", post.title);
+    markdown.push_str(&format!("- Post ID: {}\n", post.id.unwrap_or(0)));
+    markdown.push_str(&format!("- Kind: {}\n", post.kind.as_str()));
+    markdown.push_str(&format!("- Created: {}\n", post.created_at.to_rfc3339()));
+    if post.updated_at != post.created_at {
+        markdown.push_str(&format!("- Updated: {}\n", post.updated_at.to_rfc3339()));
+    }
+    if let Some(original_id) = post.original_post_id {
+        markdown.push_str(&format!("- Reshare of post: {}\n", original_id));
+    }
+    if let Some(slug) = &post.group_slug {
+        markdown.push_str(&format!("- Group: #{}\n", slug));
+    }
+    if post.hidden_at.is_some() {
+        markdown.push_str("- Hidden by moderators\n");
+    }
+    markdown.push('\n');
+    markdown.push_str(&post.content);
+    markdown.push('\n');
+
+    if let Some(poll) = &exported.poll {
+        markdown.push_str(&format!("\n## Poll: {}\n\n", poll.question));
+        for option in &poll.options {
+            markdown.push_str(&format!("- {} ({} votes)\n", option.label, option.votes));
+        }
+    }
+    markdown
+}
+
+/// Lowercase ASCII words joined by '-', for archive file names
+fn slugify(title: &str) -> String {
+    let slug = title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() { "untitled".to_string() } else { slug.chars().take(60).collect() }
+}
@@ -0,0 +1,141 @@
+// src/activity.rs
+
+use crate::models::{Page, PageRequest, Post, VisitSummary};
+use crate::database::{Database, PostOrder, VISIBLE_POSTS_FILTER};
+use rusqlite::params;
+use rusqlite::types::Value;
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashSet;
+
+/// A post is unread when someone else wrote it after the reader's last
+/// "mark all read" (or sign-up) and it has no read marker. Bind the reader as `?1`.
+const UNREAD_FILTER: &str =
+    "p.user_id != ?1
+     AND p.created_at > (SELECT COALESCE(read_all_at, created_at) FROM users WHERE id = ?1)
+     AND NOT EXISTS (SELECT 1 FROM post_reads r WHERE r.user_id = ?1 AND r.post_id = p.id)";
+
+pub struct ActivityManager {
+    db: Database,
+}
+
+impl ActivityManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Start a session: summarise what is new since the previous one and move
+    /// `last_seen_at` forward. Returns None on a user's very first visit.
+    pub fn record_visit(&self, user_id: i64) -> Result<Option<VisitSummary>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let last_seen: Option<String> = conn.query_row(
+            "SELECT last_seen_at FROM users WHERE id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+
+        let summary = match last_seen {
+            Some(last_seen) => {
+                let new_posts: i64 = conn.query_row(
+                    &format!(
+                        "SELECT COUNT(*) FROM posts p
+                         WHERE {} AND p.user_id != ?1 AND p.created_at > ?2",
+                        VISIBLE_POSTS_FILTER
+                    ),
+                    params![user_id, last_seen],
+                    |row| row.get(0),
+                )?;
+                // Comments on posts the reader can see, from people they
+                // have not muted or blocked
+                let new_comments: i64 = conn.query_row(
+                    &format!(
+                        "SELECT COUNT(*) FROM comments c
+                         JOIN posts p ON p.id = c.post_id
+                         WHERE {} AND c.hidden_at IS NULL AND c.user_id != ?1 AND c.created_at > ?2
+                           AND c.user_id NOT IN (SELECT target_user_id FROM user_blocks WHERE user_id = ?1)",
+                        VISIBLE_POSTS_FILTER
+                    ),
+                    params![user_id, last_seen],
+                    |row| row.get(0),
+                )?;
+                Some(VisitSummary {
+                    last_seen_at: chrono::DateTime::parse_from_rfc3339(&last_seen)
+                        .unwrap()
+                        .with_timezone(&Utc),
+                    new_posts,
+                    new_comments,
+                })
+            }
+            None => None,
+        };
+
+        conn.execute(
+            "UPDATE users SET last_seen_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), user_id],
+        )?;
+        Ok(summary)
+    }
+
+    pub fn mark_read(&self, user_id: i64, post_id: i64) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        conn.execute(
+            "INSERT OR IGNORE INTO post_reads (user_id, post_id, read_at) VALUES (?1, ?2, ?3)",
+            params![user_id, post_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Move the watermark to now; the individual markers are no longer needed
+    pub fn mark_all_read(&self, user_id: i64) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "UPDATE users SET read_all_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), user_id],
+        )?;
+        tx.execute("DELETE FROM post_reads WHERE user_id = ?1", params![user_id])?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// IDs of every unread post, for marking entries in list views
+    pub fn get_unread_post_ids(&self, user_id: i64) -> Result<HashSet<i64>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT p.id FROM posts p WHERE {}",
+            UNREAD_FILTER
+        ))?;
+
+        let rows = stmt.query_map(params![user_id], |row| row.get(0))?;
+
+        let mut result = HashSet::new();
+        for id in rows {
+            result.insert(id?);
+        }
+        Ok(result)
+    }
+
+    /// Unread posts the reader can see, oldest first
+    pub fn get_unread_posts(&self, user_id: i64, request: PageRequest, limit: usize) -> Result<Page<Post>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        Ok(Database::query_post_page(
+            &conn,
+            &format!("{} AND {}", VISIBLE_POSTS_FILTER, UNREAD_FILTER),
+            vec![Value::Integer(user_id)],
+            PostOrder::OldestFirst,
+            request,
+            limit,
+        )?)
+    }
+}
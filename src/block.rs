@@ -0,0 +1,77 @@
+// src/block.rs
+
+use crate::models::{BlockKind, UserBlock};
+use crate::database::Database;
+use rusqlite::params;
+use anyhow::{Error, Result};
+
+pub struct BlockManager {
+    db: Database,
+}
+
+impl BlockManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Mute or block `target_user_id`, replacing any earlier choice for that user
+    pub fn set_block(&self, user_id: i64, target_user_id: i64, kind: BlockKind) -> Result<()> {
+        if user_id == target_user_id {
+            return Err(Error::msg("You cannot mute or block yourself"));
+        }
+
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        conn.execute(
+            "INSERT INTO user_blocks (user_id, target_user_id, kind, created_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(user_id, target_user_id) DO UPDATE SET kind = excluded.kind",
+            params![user_id, target_user_id, kind.as_str(), chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Returns false when the user was neither muted nor blocked
+    pub fn remove_block(&self, user_id: i64, target_user_id: i64) -> Result<bool> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let removed = conn.execute(
+            "DELETE FROM user_blocks WHERE user_id = ?1 AND target_user_id = ?2",
+            params![user_id, target_user_id],
+        )?;
+        Ok(removed > 0)
+    }
+
+    pub fn get_user_blocks(&self, user_id: i64) -> Result<Vec<UserBlock>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT b.user_id, b.target_user_id, b.kind, b.created_at, u.username
+             FROM user_blocks b
+             JOIN users u ON b.target_user_id = u.id
+             WHERE b.user_id = ?1
+             ORDER BY u.username"
+        )?;
+
+        let rows = stmt.query_map(params![user_id], |row| {
+            Ok(UserBlock {
+                user_id: row.get(0)?,
+                target_user_id: row.get(1)?,
+                kind: BlockKind::parse(&row.get::<_, String>(2)?),
+                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+                    .unwrap()
+                    .with_timezone(&chrono::Utc),
+                target_username: Some(row.get(4)?),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for block in rows {
+            result.push(block?);
+        }
+        Ok(result)
+    }
+}
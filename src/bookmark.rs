@@ -0,0 +1,113 @@
+// src/bookmark.rs
+
+use crate::models::Bookmark;
+use crate::database::{Database, VISIBLE_POSTS_FILTER};
+use rusqlite::params;
+use anyhow::{Error, Result};
+
+pub struct BookmarkManager {
+    db: Database,
+}
+
+impl BookmarkManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub fn add_bookmark(&self, bookmark: &mut Bookmark) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        // Only posts the reader can see: not hidden, and not by anyone they
+        // muted or blocked
+        let visible: bool = conn.query_row(
+            &format!(
+                "SELECT EXISTS (SELECT 1 FROM posts p WHERE p.id = ?2 AND {})",
+                VISIBLE_POSTS_FILTER
+            ),
+            params![bookmark.user_id, bookmark.post_id],
+            |row| row.get(0),
+        )?;
+        if !visible {
+            return Err(Error::msg("Post not found"));
+        }
+
+        let mut stmt = conn.prepare(
+            "INSERT INTO bookmarks (user_id, post_id, note, created_at) VALUES (?1, ?2, ?3, ?4)"
+        )?;
+
+        let id = stmt.insert(params![
+            bookmark.user_id,
+            bookmark.post_id,
+            bookmark.note,
+            bookmark.created_at.to_rfc3339()
+        ])?;
+
+        bookmark.id = Some(id);
+        Ok(())
+    }
+
+    /// Returns false when the post was not bookmarked in the first place
+    pub fn remove_bookmark(&self, user_id: i64, post_id: i64) -> Result<bool> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let removed = conn.execute(
+            "DELETE FROM bookmarks WHERE user_id = ?1 AND post_id = ?2",
+            params![user_id, post_id],
+        )?;
+        Ok(removed > 0)
+    }
+
+    pub fn is_bookmarked(&self, user_id: i64, post_id: i64) -> Result<bool> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM bookmarks WHERE user_id = ?1 AND post_id = ?2",
+            params![user_id, post_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// The reading list, oldest bookmark first. Posts the reader can no
+    /// longer see (hidden since, or by someone they muted or blocked) stay
+    /// listed without their title and author.
+    pub fn get_user_bookmarks(&self, user_id: i64) -> Result<Vec<Bookmark>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT b.id, b.user_id, b.post_id, b.note, b.created_at,
+                    CASE WHEN {visible} THEN p.title END,
+                    CASE WHEN {visible} THEN u.username END
+             FROM bookmarks b
+             JOIN posts p ON b.post_id = p.id
+             JOIN users u ON p.user_id = u.id
+             WHERE b.user_id = ?1
+             ORDER BY b.created_at ASC, b.id ASC",
+            visible = VISIBLE_POSTS_FILTER
+        ))?;
+
+        let rows = stmt.query_map(params![user_id], |row| {
+            Ok(Bookmark {
+                id: Some(row.get(0)?),
+                user_id: row.get(1)?,
+                post_id: row.get(2)?,
+                note: row.get(3)?,
+                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                    .unwrap()
+                    .with_timezone(&chrono::Utc),
+                post_title: row.get(5)?,
+                author_username: row.get(6)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for bookmark in rows {
+            result.push(bookmark?);
+        }
+        Ok(result)
+    }
+}
@@ -1,227 +1,2687 @@
 // src/cli.rs
 
-use crate::models::{User, Post};
+use crate::models::{
+    author_label, User, Post, PostKind, Bookmark, BlockKind, Comment, Group, GroupRole, ModerationAction, Page,
+    PageRequest, Poll, Report, ReportReason, ReportTarget, Theme,
+};
 use crate::user::UserManager;
 use crate::post::PostManager;
-use anyhow::Result;
-use std::io::{self, Write};
+use crate::bookmark::BookmarkManager;
+use crate::block::BlockManager;
+use crate::moderation::ModerationManager;
+use crate::activity::ActivityManager;
+use crate::watcher::{LiveUpdates, Watcher};
+use crate::poll::PollManager;
+use crate::group::GroupManager;
+use crate::comment::CommentManager;
+use crate::notification::NotificationManager;
+use crate::account::AccountManager;
+use crate::search::SearchManager;
+use crate::saved_search::SavedSearchManager;
+use crate::ranking::{RankedPost, RankingFormula, RankingManager};
+use crate::related::RelatedManager;
+use crate::query::Query;
+use crate::pager;
+use crate::layout;
+use crate::theme::{self, Icon};
+use anyhow::{Error, Result};
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
+use crate::session::{self, SessionIo, Stdio};
+use std::io;
 
-pub struct CLI {
+/// Posts shown per page in every listing
+const PAGE_SIZE: usize = 10;
+
+/// The numbered menu and every screen behind it. All input and output goes
+/// through the session, so a CLI can run over any pair of streams.
+#[allow(clippy::upper_case_acronyms)]
+pub struct CLI<S: SessionIo = Stdio> {
+    session: S,
     user_manager: UserManager,
     post_manager: PostManager,
+    bookmark_manager: BookmarkManager,
+    block_manager: BlockManager,
+    moderation_manager: ModerationManager,
+    activity_manager: ActivityManager,
+    poll_manager: PollManager,
+    group_manager: GroupManager,
+    comment_manager: CommentManager,
+    notification_manager: NotificationManager,
+    account_manager: AccountManager,
+    search_manager: SearchManager,
+    saved_search_manager: SavedSearchManager,
+    ranking_manager: RankingManager,
+    related_manager: RelatedManager,
+    watcher: Option<Watcher>,
 }
 
-impl CLI {
-    pub fn new(user_manager: UserManager, post_manager: PostManager) -> Self {
+impl<S: SessionIo> CLI<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        session: S,
+        user_manager: UserManager,
+        post_manager: PostManager,
+        bookmark_manager: BookmarkManager,
+        block_manager: BlockManager,
+        moderation_manager: ModerationManager,
+        activity_manager: ActivityManager,
+        poll_manager: PollManager,
+        group_manager: GroupManager,
+        comment_manager: CommentManager,
+        notification_manager: NotificationManager,
+        account_manager: AccountManager,
+        search_manager: SearchManager,
+        saved_search_manager: SavedSearchManager,
+        ranking_manager: RankingManager,
+        related_manager: RelatedManager,
+    ) -> Self {
+        session::fit_output(&session);
         Self {
+            session,
             user_manager,
             post_manager,
+            bookmark_manager,
+            block_manager,
+            moderation_manager,
+            activity_manager,
+            poll_manager,
+            group_manager,
+            comment_manager,
+            notification_manager,
+            account_manager,
+            search_manager,
+            saved_search_manager,
+            ranking_manager,
+            related_manager,
+            watcher: None,
         }
     }
 
-    pub fn authenticate_user(&self) -> Result<User> {
+    /// The session the CLI talks to
+    pub fn session(&self) -> &S {
+        &self.session
+    }
+
+    /// End the CLI and hand back its session
+    pub fn into_session(self) -> S {
+        self.session
+    }
+
+    /// Show live activity banners between prompts for the rest of the session
+    pub fn attach_watcher(&mut self, watcher: Watcher) {
+        self.watcher = Some(watcher);
+    }
+
+    pub fn authenticate_user(&mut self) -> Result<User> {
         // Try the improved authentication with fallback
-        self.user_manager.authenticate_with_fallback()
+        let user = self.user_manager.authenticate_with_fallback(&mut self.session)?;
+        if user.suspended_at.is_some() {
+            return Err(Error::msg("This account has been suspended by the moderators"));
+        }
+        Ok(user)
     }
 
-    pub fn run_main_loop(&mut self, current_user: User) {
-        loop {
-            println!("\n📝 SSH Blog Platform - Welcome {}!", current_user.username);
-            println!("1. Create new post");
-            println!("2. View my posts");
-            println!("3. View all posts");
-            println!("4. Profile info");
-            println!("5. Exit");
-            print!("Choose an option (1-5): ");
-            io::stdout().flush().unwrap();
-
-            let mut input = String::new();
-            if io::stdin().read_line(&mut input).is_err() {
-                continue;
-            }
+    /// "N new posts, M new comments since <time>" for returning users; also
+    /// starts the new session
+    pub fn greet_returning_user(&mut self, user: &User) -> io::Result<()> {
+        match self.activity_manager.record_visit(user.id.unwrap()) {
+            Ok(Some(summary)) => writeln!(
+                self.session,
+                "{} {} new post(s), {} new comment(s) since {}",
+                Icon::NewPosts,
+                summary.new_posts,
+                summary.new_comments,
+                summary.last_seen_at.format("%Y-%m-%d %H:%M UTC")
+            )?,
+            Ok(None) => {}
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error checking for new posts: {}", e)))?,
+        }
+        Ok(())
+    }
 
-            match input.trim() {
-                "1" => self.create_post(&current_user),
-                "2" => self.view_user_posts(&current_user),
-                "3" => self.view_all_posts(),
-                "4" => self.show_profile(&current_user),
-                "5" => {
-                    println!("Thanks for using SSH Blog Platform! Goodbye!");
-                    break;
+    /// The numbered menu, for sessions without a terminal on both ends
+    /// (`tui::run` is used otherwise)
+    pub fn run_main_loop(&mut self, mut current_user: User) -> io::Result<()> {
+        self.show_pending_warnings(&current_user)?;
+        while self.run_menu_once(&mut current_user)? {}
+        Ok(())
+    }
+
+    /// Show the numbered menu and run the option chosen. False once the
+    /// session should end.
+    pub(crate) fn run_menu_once(&mut self, current_user: &mut User) -> io::Result<bool> {
+        self.show_live_updates(current_user)?;
+        writeln!(
+            self.session,
+            "\n{}",
+            theme::heading(format_args!(
+                "{} SSH Blog Platform - Welcome {}!",
+                Icon::Post,
+                current_user.username
+            ))
+        )?;
+        writeln!(self.session, "1. Create new post")?;
+        writeln!(self.session, "2. View my posts")?;
+        writeln!(self.session, "3. View all posts")?;
+        writeln!(self.session, "4. Search posts")?;
+        writeln!(self.session, "5. Saved searches")?;
+        writeln!(self.session, "6. Archive")?;
+        writeln!(self.session, "7. Trending & top posts")?;
+        writeln!(self.session, "8. What's new")?;
+        match self.notification_manager.count_unread(current_user.id.unwrap()) {
+            Ok(unread) if unread > 0 => writeln!(self.session, "9. Notifications ({} unread)", unread)?,
+            _ => writeln!(self.session, "9. Notifications")?,
+        }
+        writeln!(self.session, "10. Read a post")?;
+        writeln!(self.session, "11. Reading list")?;
+        writeln!(self.session, "12. Profile info")?;
+        writeln!(self.session, "13. People")?;
+        writeln!(self.session, "14. Groups")?;
+        writeln!(self.session, "15. Muted & blocked users")?;
+        writeln!(self.session, "16. Exit")?;
+        if current_user.is_admin {
+            writeln!(self.session, "A. Moderation queue")?;
+        }
+        let Some(input) = self.prompt("Choose an option (1-16): ")? else {
+            // End of input means the SSH session went away
+            return Ok(false);
+        };
+
+        match input.as_str() {
+            "1" => self.create_post(current_user)?,
+            "2" => self.view_user_posts(current_user)?,
+            "3" => self.view_all_posts(current_user)?,
+            "4" => self.search_posts(current_user)?,
+            "5" => self.manage_saved_searches(current_user)?,
+            "6" => self.browse_archive(current_user, None)?,
+            "7" => self.popular_posts(current_user)?,
+            "8" => self.whats_new(current_user)?,
+            "9" => self.view_notifications(current_user)?,
+            "10" => self.read_post(current_user)?,
+            "11" => self.view_reading_list(current_user)?,
+            "12" => {
+                if self.profile_screen(current_user)? {
+                    return Ok(false);
                 }
-                _ => println!("❌ Invalid option. Please choose 1-5."),
             }
+            "13" => self.browse_people(current_user)?,
+            "14" => self.browse_groups(current_user)?,
+            "15" => self.manage_blocks(current_user)?,
+            "A" | "a" if current_user.is_admin => self.moderation_queue(current_user)?,
+            "16" => {
+                writeln!(self.session, "Thanks for using SSH Blog Platform! Goodbye!")?;
+                return Ok(false);
+            }
+            _ => writeln!(self.session, "{}", theme::error("Invalid option. Please choose 1-16."))?,
         }
+
+        Ok(true)
     }
 
-    fn create_post(&mut self, user: &User) {
-        println!("\n✍️  Create New Post");
-        println!("{}", "=".repeat(40));
-        print!("Title: ");
-        io::stdout().flush().unwrap();
+    pub(crate) fn create_post(&mut self, user: &User) -> io::Result<()> {
+        self.create_post_in(user, None)
+    }
 
-        let mut title = String::new();
-        if io::stdin().read_line(&mut title).is_err() {
-            println!("❌ Error reading title");
-            return;
-        }
-        let title = title.trim().to_string();
+    /// Write a new post; `group` preselects the blog it is published to
+    fn create_post_in(&mut self, user: &User, group: Option<&Group>) -> io::Result<()> {
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Create New Post", Icon::Write)))?;
+        writeln!(self.session, "{}", layout::rule('='))?;
+        let title = self.prompt("Title: ")?.unwrap_or_default();
 
         if title.is_empty() {
-            println!("❌ Title cannot be empty");
-            return;
+            writeln!(self.session, "{}", theme::error("Title cannot be empty"))?;
+            return Ok(());
         }
 
-        println!("\nContent (end with a line containing only '.'):");
-        println!("{}", "-".repeat(40));
-        let mut content = String::new();
-        let mut line_count = 0;
-        
-        loop {
-            let mut line = String::new();
-            if io::stdin().read_line(&mut line).is_err() {
-                break;
-            }
-            if line.trim() == "." {
-                break;
-            }
-            content.push_str(&line);
-            line_count += 1;
-            
-            // Show progress for longer posts
-            if line_count % 10 == 0 {
-                println!("({} lines written...)", line_count);
-            }
-        }
+        writeln!(self.session, "\nContent (end with a line containing only '.'):")?;
+        writeln!(self.session, "{}", layout::rule('-'))?;
+        let content = self.read_content()?;
 
         if content.trim().is_empty() {
-            println!("❌ Content cannot be empty");
-            return;
+            writeln!(self.session, "{}", theme::error("Content cannot be empty"))?;
+            return Ok(());
         }
 
+        let mut poll = match self.prompt("Attach a poll? (y/N): ")?.as_deref() {
+            Some("y") | Some("Y") => match self.read_poll()? {
+                Some(poll) => Some(poll),
+                None => return Ok(()),
+            },
+            _ => None,
+        };
+
+        let group_id = match group {
+            Some(group) => group.id,
+            None => match self.choose_target_blog(user)? {
+                Some(target) => target,
+                None => return Ok(()),
+            },
+        };
+
         let mut post = Post::new(user.id.unwrap(), title.clone(), content.trim().to_string());
+        post.group_id = group_id;
         
-        match self.post_manager.create_post(&mut post) {
+        let created = match poll.as_mut() {
+            Some(poll) => self.post_manager.create_post_with_poll(&mut post, poll),
+            None => self.post_manager.create_post(&mut post),
+        };
+        match created {
             Ok(_) => {
-                println!("✅ Post '{}' created successfully!", title);
-                println!("📊 Post ID: {}", post.id.unwrap_or(0));
+                writeln!(self.session, "{}", theme::success(format_args!("Post '{}' created successfully!", title)))?;
+                writeln!(self.session, "{} Post ID: {}", Icon::Poll, post.id.unwrap_or(0))?;
+                if let Some(poll) = &poll {
+                    writeln!(self.session, "{} Poll attached with {} options", Icon::Poll, poll.options.len())?;
+                }
+            }
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error creating post: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    /// Personal blog or one of the user's groups. Outer None means cancelled.
+    fn choose_target_blog(&mut self, user: &User) -> io::Result<Option<Option<i64>>> {
+        let groups = self.group_manager.get_user_groups(user.id.unwrap()).unwrap_or_default();
+        if groups.is_empty() {
+            return Ok(Some(None));
+        }
+
+        writeln!(self.session, "\nPublish to:")?;
+        writeln!(self.session, "  0. My blog")?;
+        for (index, (group, _)) in groups.iter().enumerate() {
+            writeln!(self.session, "  {}. #{} ({})", index + 1, group.slug, group.name)?;
+        }
+        let Some(choice) = self.prompt("Choose a blog (Enter for your own): ")? else {
+            return Ok(None);
+        };
+        if choice.is_empty() || choice == "0" {
+            return Ok(Some(None));
+        }
+        match choice
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| groups.get(i))
+        {
+            Some((group, _)) => Ok(Some(group.id)),
+            None => {
+                writeln!(self.session, "{}", theme::error("Invalid selection"))?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Ask for a poll's question, options and settings; None if it is invalid
+    fn read_poll(&mut self) -> io::Result<Option<Poll>> {
+        let Some(question) = self.prompt("Poll question: ")? else {
+            return Ok(None);
+        };
+
+        writeln!(
+            self.session,
+            "Options, one per line ({} to {}, end with a line containing only '.'):",
+            Poll::MIN_OPTIONS,
+            Poll::MAX_OPTIONS
+        )?;
+        let labels: Vec<String> = self
+            .read_content()?
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect();
+
+        let multiple_choice = matches!(
+            self.prompt("Allow choosing several options? (y/N): ")?.as_deref(),
+            Some("y") | Some("Y")
+        );
+
+        let Some(hours) = self.prompt("Close after how many hours? (Enter for never): ")? else {
+            return Ok(None);
+        };
+        let closes_at = if hours.is_empty() {
+            None
+        } else {
+            match hours.parse::<u32>() {
+                Ok(hours) if hours > 0 => Some(Utc::now() + Duration::hours(hours as i64)),
+                _ => {
+                    writeln!(self.session, "{}", theme::error("Please enter a whole number of hours"))?;
+                    return Ok(None);
+                }
+            }
+        };
+
+        let poll = Poll::new(question, labels, multiple_choice, closes_at);
+        match PollManager::validate(&poll) {
+            Ok(_) => Ok(Some(poll)),
+            Err(e) => {
+                writeln!(self.session, "{}", theme::error(e))?;
+                Ok(None)
             }
-            Err(e) => println!("❌ Error creating post: {}", e),
         }
     }
 
-    fn view_user_posts(&self, user: &User) {
-        println!("\n📚 Your Posts");
-        println!("{}", "=".repeat(50));
+    fn view_user_posts(&mut self, user: &User) -> io::Result<()> {
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Your Posts", Icon::Posts)))?;
+        writeln!(self.session, "{}", layout::rule('='))?;
+
+        let user_id = user.id.unwrap();
+        let shown = self.browse_posts(
+            |cli, request| cli.post_manager.get_user_posts(user_id, request, PAGE_SIZE),
+            |cli, number, post| {
+                writeln!(cli.session, "\n{}", theme::heading(format_args!("{} Post #{}", Icon::Page, number)))?;
+                cli.display_post(user_id, post)
+            },
+            "",
+            |_, _, _| Ok(false),
+        )?;
+        if shown == Some(false) {
+            writeln!(self.session, "{} No posts yet. Create your first post!", Icon::Post)?;
+            writeln!(self.session, "{}", theme::tip("Choose option 1 from the main menu to get started."))?;
+        }
+        Ok(())
+    }
+
+    fn view_all_posts(&mut self, user: &User) -> io::Result<()> {
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} All Posts", Icon::AllPosts)))?;
+        writeln!(self.session, "{}", layout::rule('='))?;
         
-        match self.post_manager.get_user_posts(user.id.unwrap()) {
-            Ok(posts) => {
-                if posts.is_empty() {
-                    println!("📝 No posts yet. Create your first post!");
-                    println!("💡 Choose option 1 from the main menu to get started.");
+        let user_id = user.id.unwrap();
+        let unread = self.activity_manager.get_unread_post_ids(user_id).unwrap_or_default();
+
+        let shown = self.browse_posts(
+            |cli, request| cli.post_manager.get_all_posts(user_id, request, PAGE_SIZE),
+            |cli, number, post| {
+                let post_id = post.id.unwrap();
+                if unread.contains(&post_id) {
+                    writeln!(
+                        cli.session,
+                        "\n{}",
+                        theme::heading(format_args!("{} Post #{} {}", Icon::Page, number, Icon::New))
+                    )?;
                 } else {
-                    println!("📊 Found {} post(s)", posts.len());
-                    for (index, post) in posts.iter().enumerate() {
-                        println!("\n📄 Post #{}", index + 1);
-                        self.display_post(post);
+                    writeln!(cli.session, "\n{}", theme::heading(format_args!("{} Post #{}", Icon::Page, number)))?;
+                }
+                cli.display_post_with_author(user_id, post)?;
+                if let Err(e) = cli.activity_manager.mark_read(user_id, post_id) {
+                    writeln!(cli.session, "{}", theme::error(format_args!("Error saving read marker: {}", e)))?;
+                }
+                Ok(())
+            },
+            "",
+            |_, _, _| Ok(false),
+        )?;
+        if shown == Some(false) {
+            writeln!(self.session, "{} No posts available on the platform yet.", Icon::Post)?;
+            writeln!(self.session, "{} Be the first to create a post!", Icon::Welcome)?;
+        }
+        Ok(())
+    }
+
+    /// Page through a post listing, PAGE_SIZE posts at a time, with n and p.
+    /// `show` prints one post given its number in the whole listing; any
+    /// other input goes to `handle` with the page on screen, which returns
+    /// true when it was understood. Each is handed the CLI, since the session
+    /// can't be borrowed twice. Returns Some(false) when the listing was
+    /// empty and None when it could not be fetched.
+    fn browse_posts(
+        &mut self,
+        fetch: impl Fn(&Self, PageRequest) -> Result<Page<Post>>,
+        show: impl Fn(&mut Self, usize, &Post) -> io::Result<()>,
+        hint: &str,
+        mut handle: impl FnMut(&mut Self, &str, &[(usize, Post)]) -> io::Result<bool>,
+    ) -> io::Result<Option<bool>> {
+        let mut request = PageRequest::First;
+        let mut page_index = 0;
+
+        loop {
+            let page = match fetch(self, request) {
+                Ok(page) => page,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error fetching posts: {}", e)))?;
+                    return Ok(None);
+                }
+            };
+            if page.items.is_empty() {
+                // Whatever was on this page has gone since; start over
+                if request != PageRequest::First {
+                    request = PageRequest::First;
+                    page_index = 0;
+                    continue;
+                }
+                return Ok(Some(false));
+            }
+
+            let first_number = page_index * PAGE_SIZE + 1;
+            let numbered: Vec<(usize, Post)> =
+                page.items.into_iter().enumerate().map(|(i, post)| (first_number + i, post)).collect();
+            for (number, post) in &numbered {
+                show(self, *number, post)?;
+            }
+            writeln!(
+                self.session,
+                "\n{}",
+                theme::heading(format_args!(
+                    "{} Page {} {} posts {}-{}",
+                    Icon::Page,
+                    page_index + 1,
+                    Icon::Separator,
+                    first_number,
+                    first_number + numbered.len() - 1
+                ))
+            )?;
+
+            let mut options = Vec::new();
+            if page.next.is_some() {
+                options.push("n next page");
+            }
+            if page.prev.is_some() {
+                options.push("p previous page");
+            }
+            if !hint.is_empty() {
+                options.push(hint);
+            }
+            options.push("q back");
+            let Some(choice) = self.prompt(&format!("{}: ", options.join(", ")))? else {
+                return Ok(Some(true));
+            };
+
+            match (choice.as_str(), page.next, page.prev) {
+                ("" | "q", _, _) => return Ok(Some(true)),
+                ("n", Some(cursor), _) => {
+                    request = PageRequest::After(cursor);
+                    page_index += 1;
+                }
+                ("n", None, _) => writeln!(self.session, "That was the last page")?,
+                ("p", _, Some(cursor)) => {
+                    request = PageRequest::Before(cursor);
+                    page_index = page_index.saturating_sub(1);
+                }
+                ("p", _, None) => writeln!(self.session, "This is the first page")?,
+                (other, _, _) => {
+                    if !handle(self, other, &numbered)? {
+                        writeln!(self.session, "{}", theme::error("Invalid option"))?;
                     }
                 }
             }
-            Err(e) => println!("❌ Error fetching posts: {}", e),
         }
     }
 
-    fn view_all_posts(&self) {
-        println!("\n🌍 All Posts");
-        println!("{}", "=".repeat(50));
-        
-        match self.post_manager.get_all_posts() {
-            Ok(posts) => {
-                if posts.is_empty() {
-                    println!("📝 No posts available on the platform yet.");
-                    println!("🚀 Be the first to create a post!");
+    fn search_posts(&mut self, user: &User) -> io::Result<()> {
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Search posts", Icon::Search)))?;
+        writeln!(self.session, "Words and \"exact phrases\", plus filters: author:name before:YYYY-MM-DD")?;
+        writeln!(self.session, "after:YYYY-MM-DD in:title in:body. Put '-' in front of anything to exclude it.")?;
+        let Some(input) = self.prompt("Search for: ")?.filter(|q| !q.is_empty()) else {
+            return Ok(());
+        };
+        let Some(query) = self.parse_query(&input)? else {
+            return Ok(());
+        };
+        self.show_search_results(user, &input, &query, true)?;
+        Ok(())
+    }
+
+    /// Parse a search, pointing at what's wrong when it doesn't parse
+    fn parse_query(&mut self, input: &str) -> io::Result<Option<Query>> {
+        match Query::parse(input) {
+            Ok(query) => Ok(Some(query)),
+            Err(e) => {
+                writeln!(self.session, "{}", theme::error(&e))?;
+                writeln!(self.session, "{}", e.pointer(input))?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Run a search and page through what it finds; `can_save` offers to keep it
+    fn show_search_results(&mut self, user: &User, input: &str, query: &Query, mut can_save: bool) -> io::Result<()> {
+        loop {
+            let results = match self.search_manager.search(user.id.unwrap(), query, 20) {
+                Ok(results) => results,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Search failed: {}", e)))?;
+                    return Ok(());
+                }
+            };
+
+            writeln!(self.session, "\n{}", theme::heading(format_args!("{} Results for {}", Icon::Search, input)))?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+            if results.is_empty() {
+                writeln!(self.session, "{} No posts match your search.", Icon::Empty)?;
+                if !can_save {
+                    return Ok(());
+                }
+            }
+
+            for (index, result) in results.iter().enumerate() {
+                let post = &result.post;
+                writeln!(
+                    self.session,
+                    "{:3}. {} by {} ({})",
+                    index + 1,
+                    post.title,
+                    post.author_username.as_deref().unwrap_or("unknown"),
+                    post.created_at.format("%Y-%m-%d")
+                )?;
+                writeln!(self.session, "{}", layout::indent("     ", &result.snippet.replace('\n', " ")))?;
+            }
+
+            let hint = match (can_save, results.is_empty()) {
+                (true, true) => "\ns to save this search and hear about new matches, or Enter to go back: ",
+                (true, false) => "\nEnter a number to read, s to save this search, or Enter to go back: ",
+                (false, _) => "\nEnter a number to read, or Enter to go back: ",
+            };
+            let Some(choice) = self.prompt(hint)? else {
+                return Ok(());
+            };
+            if choice.is_empty() {
+                return Ok(());
+            }
+            if choice == "s" && can_save {
+                if self.save_search(user, input)? {
+                    can_save = false;
+                }
+                continue;
+            }
+            match choice
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| results.get(i))
+            {
+                Some(result) => self.open_post(user, result.post.id.unwrap())?,
+                None => writeln!(self.session, "{}", theme::error("Invalid selection"))?,
+            }
+        }
+    }
+
+    /// Ask for a name and whether to alert, then save the search. True if saved.
+    fn save_search(&mut self, user: &User, input: &str) -> io::Result<bool> {
+        let Some(name) = self.prompt("Name for this search: ")?.filter(|name| !name.is_empty()) else {
+            writeln!(self.session, "{}", theme::error("The search needs a name to be saved"))?;
+            return Ok(false);
+        };
+        let alerts = matches!(
+            self.prompt("Notify you when new posts match? (y/N): ")?.as_deref(),
+            Some("y") | Some("Y")
+        );
+
+        match self.saved_search_manager.save_search(user.id.unwrap(), &name, input, alerts) {
+            Ok(saved) => {
+                if saved.alerts {
+                    writeln!(
+                        self.session,
+                        "{}",
+                        theme::success(format_args!(
+                            "Saved '{}'. You'll be notified when new posts match.",
+                            saved.name
+                        ))
+                    )?;
                 } else {
-                    println!("📊 Found {} post(s) on the platform", posts.len());
-                    for (index, post) in posts.iter().enumerate() {
-                        println!("\n📄 Post #{}", index + 1);
-                        self.display_post_with_author(post);
-                    }
+                    writeln!(self.session, "{}", theme::success(format_args!("Saved '{}'", saved.name)))?;
                 }
+                Ok(true)
+            }
+            Err(e) => {
+                writeln!(self.session, "{}", theme::error(format_args!("Error saving the search: {}", e)))?;
+                Ok(false)
             }
-            Err(e) => println!("❌ Error fetching posts: {}", e),
         }
     }
 
-    fn show_profile(&self, user: &User) {
-        println!("\n👤 Profile Information");
-        println!("{}", "=".repeat(40));
-        println!("Username: {}", user.username);
-        println!("User ID: {}", user.id.unwrap_or(0));
-        println!("Joined: {}", user.created_at.format("%Y-%m-%d %H:%M UTC"));
-        
-        if let Some(bio) = &user.bio {
-            println!("Bio: {}", bio);
-        } else {
-            println!("Bio: (not set)");
+    fn manage_saved_searches(&mut self, user: &User) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+
+        loop {
+            writeln!(self.session, "\n{}", theme::heading(format_args!("{} Saved searches", Icon::Saved)))?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+
+            let searches = match self.saved_search_manager.get_saved_searches(user_id) {
+                Ok(searches) => searches,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error fetching saved searches: {}", e)))?;
+                    return Ok(());
+                }
+            };
+
+            if searches.is_empty() {
+                writeln!(self.session, "Nothing saved yet. Save a search from its results, or with n below.")?;
+            }
+            for (index, saved) in searches.iter().enumerate() {
+                writeln!(
+                    self.session,
+                    "{:3}. {} {} {} {}",
+                    index + 1,
+                    if saved.alerts { Icon::AlertsOn } else { Icon::AlertsOff },
+                    saved.name,
+                    Icon::Dash,
+                    saved.query
+                )?;
+            }
+
+            let Some(choice) = self.prompt(
+                "\nEnter a number to run, n for a new search, a<number> to turn alerts on or off, d<number> to delete, or Enter to go back: ",
+            )? else {
+                return Ok(());
+            };
+            match choice.as_str() {
+                "" => return Ok(()),
+                "n" => {
+                    let Some(input) = self.prompt("Search for: ")?.filter(|q| !q.is_empty()) else {
+                        continue;
+                    };
+                    if self.parse_query(&input)?.is_some() {
+                        self.save_search(user, &input)?;
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            let (action, number) = if let Some(rest) = choice.strip_prefix('a') {
+                (Some('a'), rest.trim())
+            } else if let Some(rest) = choice.strip_prefix('d') {
+                (Some('d'), rest.trim())
+            } else {
+                (None, choice.as_str())
+            };
+            let Some(saved) = number
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| searches.get(i))
+            else {
+                writeln!(self.session, "{}", theme::error("Invalid selection"))?;
+                continue;
+            };
+
+            match action {
+                None => {
+                    if let Some(query) = self.parse_query(&saved.query)? {
+                        self.show_search_results(user, &saved.query, &query, false)?;
+                    }
+                }
+                Some('a') => match self.saved_search_manager.set_alerts(user_id, saved.id, !saved.alerts) {
+                    Ok(_) if saved.alerts => writeln!(self.session, "{} Alerts off for '{}'", Icon::AlertsOff, saved.name)?,
+                    Ok(_) => writeln!(
+                        self.session,
+                        "{} You'll be notified when new posts match '{}'",
+                        Icon::AlertsOn,
+                        saved.name
+                    )?,
+                    Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error updating alerts: {}", e)))?,
+                },
+                Some(_) => match self.saved_search_manager.delete_saved_search(user_id, saved.id) {
+                    Ok(_) => writeln!(self.session, "{} Deleted '{}'", Icon::Deleted, saved.name)?,
+                    Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error deleting the search: {}", e)))?,
+                },
+            }
         }
+    }
 
-        // Show post count
-        match self.post_manager.get_user_posts(user.id.unwrap()) {
-            Ok(posts) => {
-                println!("Total posts: {}", posts.len());
+    /// Year → month tree of post counts, for the whole site or one author;
+    /// picking a month pages through its posts
+    fn browse_archive(&mut self, viewer: &User, author: Option<&User>) -> io::Result<()> {
+        let viewer_id = viewer.id.unwrap();
+        let author_id = author.and_then(|author| author.id);
+
+        loop {
+            match author {
+                Some(author) => writeln!(
+                    self.session,
+                    "\n{}",
+                    theme::heading(format_args!("{} Archive of {}", Icon::Calendar, author.display_label()))
+                )?,
+                None => writeln!(self.session, "\n{}", theme::heading(format_args!("{} Archive", Icon::Calendar)))?,
             }
-            Err(_) => {
-                println!("Total posts: (error fetching)");
+            writeln!(self.session, "{}", layout::rule('='))?;
+
+            let months = match self.post_manager.get_archive(viewer_id, author_id) {
+                Ok(months) => months,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error fetching the archive: {}", e)))?;
+                    return Ok(());
+                }
+            };
+            if months.is_empty() {
+                writeln!(self.session, "{} Nothing has been posted yet.", Icon::Post)?;
+                return Ok(());
+            }
+
+            let mut year = None;
+            for (index, month) in months.iter().enumerate() {
+                if year != Some(month.year) {
+                    let total: i64 = months.iter().filter(|m| m.year == month.year).map(|m| m.count).sum();
+                    writeln!(
+                        self.session,
+                        "\n{}",
+                        theme::heading(format_args!("{} {} ({} post(s))", Icon::Date, month.year, total))
+                    )?;
+                    year = Some(month.year);
+                }
+                writeln!(self.session, "{:5}. {:<10} {:4}", index + 1, month.month_name(), month.count)?;
             }
+
+            let Some(choice) = self.prompt("\nEnter a number to see that month's posts, or Enter to go back: ")? else {
+                return Ok(());
+            };
+            if choice.is_empty() {
+                return Ok(());
+            }
+            let Some(month) = choice
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| months.get(i))
+            else {
+                writeln!(self.session, "{}", theme::error("Invalid selection"))?;
+                continue;
+            };
+
+            writeln!(
+                self.session,
+                "\n{}",
+                theme::heading(format_args!("{} {} {}", Icon::Calendar, month.month_name(), month.year))
+            )?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+            self.browse_posts(
+                |cli, request| cli.post_manager.get_month_posts(viewer_id, author_id, month, request, PAGE_SIZE),
+                |cli, number, post| {
+                    writeln!(cli.session, "\n{}", theme::heading(format_args!("{} Post #{}", Icon::Page, number)))?;
+                    cli.display_post_with_author(viewer_id, post)
+                },
+                "",
+                |_, _, _| Ok(false),
+            )?;
         }
     }
 
-    fn display_post(&self, post: &Post) {
-        println!("{}", "─".repeat(50));
-        println!("📝 {}", post.title);
-        println!("📅 Created: {}", post.created_at.format("%Y-%m-%d %H:%M UTC"));
-        if post.updated_at != post.created_at {
-            println!("📝 Updated: {}", post.updated_at.format("%Y-%m-%d %H:%M UTC"));
+    /// Trending this week, with the all-time top one key away
+    fn popular_posts(&mut self, user: &User) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+        let mut all_time = false;
+
+        loop {
+            let ranked: Result<Vec<RankedPost>> = if all_time {
+                writeln!(self.session, "\n{}", theme::heading(format_args!("{} All-time top", Icon::Top)))?;
+                self.ranking_manager.get_all_time_top(user_id, 20)
+            } else {
+                writeln!(self.session, "\n{}", theme::heading(format_args!("{} Trending this week", Icon::Trending)))?;
+                self.ranking_manager.get_trending(user_id, 20)
+            };
+            writeln!(self.session, "{}", layout::rule('='))?;
+            let ranked = match ranked {
+                Ok(ranked) => ranked,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error fetching posts: {}", e)))?;
+                    return Ok(());
+                }
+            };
+
+            if ranked.is_empty() {
+                writeln!(self.session, "{} Nothing has caught on yet.", Icon::Empty)?;
+            }
+            for (index, entry) in ranked.iter().enumerate() {
+                let post = &entry.post;
+                writeln!(
+                    self.session,
+                    "{:3}. {} by {} ({}) {} score {:.1}",
+                    index + 1,
+                    post.title,
+                    post.author_username.as_deref().unwrap_or("unknown"),
+                    post.created_at.format("%Y-%m-%d"),
+                    Icon::Separator,
+                    entry.score
+                )?;
+            }
+
+            let switch = if all_time { "t for trending this week" } else { "a for the all-time top" };
+            let Some(choice) = self.prompt(&format!("\nEnter a number to read, {}, or Enter to go back: ", switch))?
+            else {
+                return Ok(());
+            };
+            match choice.as_str() {
+                "" => return Ok(()),
+                "a" => all_time = true,
+                "t" => all_time = false,
+                _ => match choice
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|i| ranked.get(i))
+                {
+                    Some(entry) => self.open_post(user, entry.post.id.unwrap())?,
+                    None => writeln!(self.session, "{}", theme::error("Invalid selection"))?,
+                },
+            }
         }
-        println!("{}", "─".repeat(50));
-        
-        // Display content with line numbers for longer posts
-        let lines: Vec<&str> = post.content.lines().collect();
-        if lines.len() > 20 {
-            for (i, line) in lines.iter().enumerate() {
-                println!("{:3}: {}", i + 1, line);
+    }
+
+    fn read_post(&mut self, user: &User) -> io::Result<()> {
+        let Some(input) = self.prompt(&format!("\n{} Post ID: ", Icon::Find))? else {
+            return Ok(());
+        };
+        let Ok(post_id) = input.parse::<i64>() else {
+            writeln!(self.session, "{}", theme::error("Invalid post ID"))?;
+            return Ok(());
+        };
+        self.open_post(user, post_id)?;
+        Ok(())
+    }
+
+    /// Show a single post, mark it read and offer the post actions
+    pub(crate) fn open_post(&mut self, user: &User, post_id: i64) -> io::Result<()> {
+        match self.post_manager.get_post(post_id) {
+            Ok(Some(post)) if post.hidden_at.is_some() && post.user_id != user.id.unwrap() && !user.is_admin => {
+                writeln!(self.session, "{} This post has been hidden by the moderators", Icon::Hidden)?;
+            }
+            Ok(Some(post)) => {
+                self.display_post_with_author(user.id.unwrap(), &post)?;
+                self.display_comments(user, post_id)?;
+                if let Err(e) = self.activity_manager.mark_read(user.id.unwrap(), post_id) {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error saving read marker: {}", e)))?;
+                }
+                if let Err(e) = self.ranking_manager.record_view(user.id.unwrap(), post_id) {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error counting the view: {}", e)))?;
+                }
+                self.post_actions(user, &post)?;
             }
+            Ok(None) => writeln!(self.session, "{}", theme::error(format_args!("Post {} not found", post_id)))?,
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error fetching post: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    fn whats_new(&mut self, user: &User) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} What's New", Icon::New)))?;
+        writeln!(self.session, "{}", layout::rule('='))?;
+
+        let shown = self.browse_posts(
+            |cli, request| cli.activity_manager.get_unread_posts(user_id, request, PAGE_SIZE),
+            |cli, number, post| {
+                writeln!(
+                    cli.session,
+                    "{:3}. {} by {} ({})",
+                    number,
+                    post.title,
+                    post.author_username.as_deref().unwrap_or("unknown"),
+                    post.created_at.format("%Y-%m-%d %H:%M UTC")
+                )
+            },
+            "a number to read, m mark all read",
+            |cli, choice, posts| {
+                // Read posts drop out of the listing when the page is fetched again
+                if choice == "m" {
+                    match cli.activity_manager.mark_all_read(user_id) {
+                        Ok(_) => writeln!(cli.session, "{}", theme::success("Everything marked as read"))?,
+                        Err(e) => writeln!(
+                            cli.session,
+                            "{}",
+                            theme::error(format_args!("Error marking posts read: {}", e))
+                        )?,
+                    }
+                    return Ok(true);
+                }
+                let Ok(number) = choice.parse::<usize>() else {
+                    return Ok(false);
+                };
+                match posts.iter().find(|(n, _)| *n == number) {
+                    Some((_, post)) => cli.open_post(user, post.id.unwrap())?,
+                    None => writeln!(cli.session, "{}", theme::error("Invalid selection"))?,
+                }
+                Ok(true)
+            },
+        )?;
+        if shown == Some(false) {
+            writeln!(self.session, "{}", theme::success("You're all caught up!"))?;
+        }
+        Ok(())
+    }
+
+    fn post_actions(&mut self, user: &User, post: &Post) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+        let post_id = post.id.unwrap();
+        let bookmarked = self.bookmark_manager.is_bookmarked(user_id, post_id).unwrap_or(false);
+
+        if bookmarked {
+            writeln!(self.session, "r. Remove from reading list")?;
         } else {
-            println!("{}", post.content);
+            writeln!(self.session, "b. Bookmark this post")?;
         }
-        
-        println!("{}", "─".repeat(50));
+        let can_reshare = post.user_id != user_id;
+        if can_reshare {
+            writeln!(self.session, "s. Reblog")?;
+            writeln!(self.session, "q. Quote with commentary")?;
+        }
+        let open_poll = self
+            .poll_manager
+            .get_poll_for_post(post_id)
+            .ok()
+            .flatten()
+            .filter(|poll| !poll.is_closed())
+            .filter(|poll| !self.poll_manager.has_voted(poll.id.unwrap(), user_id).unwrap_or(true));
+        if open_poll.is_some() {
+            writeln!(self.session, "v. Vote in the poll")?;
+        }
+        writeln!(self.session, "c. Comment")?;
+        let subscribed = self.comment_manager.is_subscribed(user_id, post_id).unwrap_or(false);
+        if subscribed {
+            writeln!(self.session, "u. Unsubscribe from comments")?;
+        } else {
+            writeln!(self.session, "f. Follow comments")?;
+        }
+        writeln!(self.session, "x. Report")?;
+        let Some(choice) = self.prompt("Choose an action (Enter to go back): ")? else {
+            return Ok(());
+        };
+
+        match choice.as_str() {
+            "b" if !bookmarked => {
+                let note = self
+                    .prompt("Private note (optional, press Enter to skip): ")?
+                    .filter(|n| !n.is_empty());
+                let mut bookmark = Bookmark::new(user_id, post_id, note);
+                match self.bookmark_manager.add_bookmark(&mut bookmark) {
+                    Ok(_) => writeln!(self.session, "{} Added '{}' to your reading list", Icon::Bookmark, post.title)?,
+                    Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error saving bookmark: {}", e)))?,
+                }
+            }
+            "r" if bookmarked => match self.bookmark_manager.remove_bookmark(user_id, post_id) {
+                Ok(_) => writeln!(
+                    self.session,
+                    "{}",
+                    theme::success(format_args!("Removed '{}' from your reading list", post.title))
+                )?,
+                Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error removing bookmark: {}", e)))?,
+            },
+            "s" | "q" if can_reshare => self.reshare_post(user, post, choice == "q")?,
+            "v" if open_poll.is_some() => self.vote_in_poll(user, open_poll.as_ref().unwrap())?,
+            "c" => self.add_comment(user, post)?,
+            "u" if subscribed => self.unsubscribe_from_comments(user, post_id)?,
+            "f" if !subscribed => match self.comment_manager.set_subscribed(user_id, post_id, true) {
+                Ok(_) => writeln!(
+                    self.session,
+                    "{} You'll be notified about new comments on '{}'",
+                    Icon::AlertsOn,
+                    post.title
+                )?,
+                Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error updating subscription: {}", e)))?,
+            },
+            "x" => self.report_content(user, post)?,
+            "" => {}
+            _ => writeln!(self.session, "{}", theme::error("Invalid action"))?,
+        }
+        Ok(())
     }
 
-    fn display_post_with_author(&self, post: &Post) {
-        println!("{}", "─".repeat(50));
-        println!("📝 {}", post.title);
-        if let Some(username) = &post.author_username {
-            println!("👤 Author: {}", username);
+    fn add_comment(&mut self, user: &User, post: &Post) -> io::Result<()> {
+        writeln!(self.session, "{} Write your comment (end with a line containing only '.'):", Icon::Comment)?;
+        let content = self.read_content()?;
+        if content.trim().is_empty() {
+            writeln!(self.session, "{}", theme::error("Comment cannot be empty"))?;
+            return Ok(());
+        }
+
+        let mut comment = Comment::new(post.id.unwrap(), user.id.unwrap(), content);
+        match self.comment_manager.add_comment(&mut comment) {
+            Ok(_) => writeln!(
+                self.session,
+                "{}",
+                theme::success("Comment added. You'll be notified about replies to this thread.")
+            )?,
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error adding comment: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    fn unsubscribe_from_comments(&mut self, user: &User, post_id: i64) -> io::Result<()> {
+        match self.comment_manager.set_subscribed(user.id.unwrap(), post_id, false) {
+            Ok(_) => writeln!(
+                self.session,
+                "{} Unsubscribed. You won't be notified about new comments on this post.",
+                Icon::AlertsOff
+            )?,
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error updating subscription: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    fn view_notifications(&mut self, user: &User) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+
+        loop {
+            writeln!(self.session, "\n{}", theme::heading(format_args!("{} Notifications", Icon::Notifications)))?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+
+            let notifications = match self.notification_manager.get_notifications(user_id, 50) {
+                Ok(notifications) => notifications,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error fetching notifications: {}", e)))?;
+                    return Ok(());
+                }
+            };
+
+            if notifications.is_empty() {
+                writeln!(self.session, "{} No notifications yet.", Icon::Empty)?;
+                return Ok(());
+            }
+
+            for (index, notification) in notifications.iter().enumerate() {
+                writeln!(
+                    self.session,
+                    "{:3}. {}{} ({})",
+                    index + 1,
+                    if notification.read_at.is_none() { format!("{} ", Icon::New) } else { String::new() },
+                    notification.message,
+                    notification.created_at.format("%Y-%m-%d %H:%M UTC")
+                )?;
+            }
+
+            let Some(choice) = self.prompt(
+                "\nEnter a number to open, u<number> to unsubscribe from that thread, m to mark all read, or Enter to go back: ",
+            )? else {
+                return Ok(());
+            };
+            match choice.as_str() {
+                "" => return Ok(()),
+                "m" => {
+                    match self.notification_manager.mark_all_read(user_id) {
+                        Ok(_) => writeln!(self.session, "{}", theme::success("All notifications marked as read"))?,
+                        Err(e) => writeln!(
+                            self.session,
+                            "{}",
+                            theme::error(format_args!("Error marking notifications read: {}", e))
+                        )?,
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            let (unsubscribe, number) = match choice.strip_prefix('u') {
+                Some(rest) => (true, rest.trim()),
+                None => (false, choice.as_str()),
+            };
+            let Some(notification) = number
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| notifications.get(i))
+            else {
+                writeln!(self.session, "{}", theme::error("Invalid selection"))?;
+                continue;
+            };
+
+            if let Err(e) = self.notification_manager.mark_read(user_id, notification.id) {
+                writeln!(self.session, "{}", theme::error(format_args!("Error marking notification read: {}", e)))?;
+            }
+            match (notification.post_id, unsubscribe) {
+                (Some(post_id), true) => self.unsubscribe_from_comments(user, post_id)?,
+                (Some(post_id), false) => self.open_post(user, post_id)?,
+                (None, _) => {}
+            }
+        }
+    }
+
+    fn vote_in_poll(&mut self, user: &User, poll: &Poll) -> io::Result<()> {
+        let label = if poll.multiple_choice {
+            "Your choices (e.g. 1,3): "
         } else {
-            println!("👤 Author ID: {}", post.user_id);
+            "Your choice: "
+        };
+        let Some(input) = self.prompt(label)? else {
+            return Ok(());
+        };
+
+        let mut choices = Vec::new();
+        for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.parse::<usize>().ok().and_then(|n| n.checked_sub(1)) {
+                Some(index) if index < poll.options.len() => {
+                    if !choices.contains(&index) {
+                        choices.push(index);
+                    }
+                }
+                _ => {
+                    writeln!(self.session, "{}", theme::error(format_args!("'{}' is not one of the options", part)))?;
+                    return Ok(());
+                }
+            }
         }
-        println!("📅 Created: {}", post.created_at.format("%Y-%m-%d %H:%M UTC"));
-        if post.updated_at != post.created_at {
-            println!("📝 Updated: {}", post.updated_at.format("%Y-%m-%d %H:%M UTC"));
+
+        match self.poll_manager.vote(poll, user.id.unwrap(), &choices) {
+            Ok(_) => {
+                writeln!(self.session, "{}", theme::success("Vote recorded"))?;
+                if let Ok(Some(updated)) = self.poll_manager.get_poll_for_post(poll.post_id) {
+                    self.display_poll(&updated)?;
+                }
+            }
+            Err(e) => writeln!(self.session, "{}", theme::error(e))?,
         }
-        println!("{}", "─".repeat(50));
-        
-        // Display content
-        let lines: Vec<&str> = post.content.lines().collect();
-        if lines.len() > 20 {
-            for (i, line) in lines.iter().enumerate() {
-                println!("{:3}: {}", i + 1, line);
+        Ok(())
+    }
+
+    fn reshare_post(&mut self, user: &User, post: &Post, quote: bool) -> io::Result<()> {
+        // Resharing a reblog reshares what it points at
+        let original = if post.kind == PostKind::Reblog {
+            match self.load_original(post) {
+                Some(original) => original,
+                None => {
+                    writeln!(self.session, "{}", theme::error("The original post is unavailable"))?;
+                    return Ok(());
+                }
             }
         } else {
-            println!("{}", post.content);
+            post.clone()
+        };
+        if original.user_id == user.id.unwrap() {
+            writeln!(self.session, "{}", theme::error("You cannot reshare your own post"))?;
+            return Ok(());
         }
-        
-        println!("{}", "─".repeat(50));
+
+        let commentary = if quote {
+            writeln!(self.session, "\nYour commentary (end with a line containing only '.'):")?;
+            writeln!(self.session, "{}", layout::rule('-'))?;
+            let content = self.read_content()?;
+            if content.trim().is_empty() {
+                writeln!(self.session, "{}", theme::error("Commentary cannot be empty"))?;
+                return Ok(());
+            }
+            Some(content.trim().to_string())
+        } else {
+            None
+        };
+
+        let mut reshare = Post::new_reshare(user.id.unwrap(), &original, commentary);
+        match self.post_manager.create_post(&mut reshare) {
+            Ok(_) if quote => writeln!(
+                self.session,
+                "{}",
+                theme::success(format_args!(
+                    "Quoted '{}' (Post ID: {})",
+                    original.title,
+                    reshare.id.unwrap_or(0)
+                ))
+            )?,
+            Ok(_) => writeln!(
+                self.session,
+                "{} Reblogged '{}' (Post ID: {})",
+                Icon::Reblog,
+                original.title,
+                reshare.id.unwrap_or(0)
+            )?,
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error resharing post: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    /// The post a reblog or quote points at, if it still exists
+    fn load_original(&self, post: &Post) -> Option<Post> {
+        let original_id = post.original_post_id?;
+        self.post_manager
+            .get_post(original_id)
+            .ok()
+            .flatten()
+            .filter(|original| original.hidden_at.is_none())
+    }
+
+    fn view_reading_list(&mut self, user: &User) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+
+        loop {
+            writeln!(self.session, "\n{}", theme::heading(format_args!("{} Reading List", Icon::Bookmark)))?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+
+            let bookmarks = match self.bookmark_manager.get_user_bookmarks(user_id) {
+                Ok(bookmarks) => bookmarks,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error fetching reading list: {}", e)))?;
+                    return Ok(());
+                }
+            };
+
+            if bookmarks.is_empty() {
+                writeln!(self.session, "{} Your reading list is empty.", Icon::Post)?;
+                writeln!(
+                    self.session,
+                    "{}",
+                    theme::tip("Open a post with 'Read a post' and press 'b' to save it for later.")
+                )?;
+                return Ok(());
+            }
+
+            for (index, bookmark) in bookmarks.iter().enumerate() {
+                let post = match (&bookmark.post_title, &bookmark.author_username) {
+                    (Some(title), Some(author)) => format!("{} by {}", title, author),
+                    _ => "Post unavailable".to_string(),
+                };
+                writeln!(
+                    self.session,
+                    "{:3}. {} (Post ID: {}, saved {})",
+                    index + 1,
+                    post,
+                    bookmark.post_id,
+                    bookmark.created_at.format("%Y-%m-%d")
+                )?;
+                if let Some(note) = &bookmark.note {
+                    writeln!(self.session, "     {} {}", Icon::Note, note)?;
+                }
+            }
+
+            let Some(choice) =
+                self.prompt("\nEnter a number to read, r<number> to remove, or Enter to go back: ")?
+            else {
+                return Ok(());
+            };
+            if choice.is_empty() {
+                return Ok(());
+            }
+
+            let (remove, number) = match choice.strip_prefix('r') {
+                Some(rest) => (true, rest.trim()),
+                None => (false, choice.as_str()),
+            };
+            let Some(bookmark) = number
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| bookmarks.get(i))
+            else {
+                writeln!(self.session, "{}", theme::error("Invalid selection"))?;
+                continue;
+            };
+
+            if remove {
+                match self.bookmark_manager.remove_bookmark(user_id, bookmark.post_id) {
+                    Ok(_) => writeln!(self.session, "{}", theme::success("Removed from your reading list"))?,
+                    Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error removing bookmark: {}", e)))?,
+                }
+            } else {
+                self.open_post(user, bookmark.post_id)?;
+            }
+        }
+    }
+
+    fn report_content(&mut self, user: &User, post: &Post) -> io::Result<()> {
+        let comments = self
+            .comment_manager
+            .get_post_comments(post.id.unwrap(), user.id.unwrap())
+            .unwrap_or_default();
+
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Report", Icon::Report)))?;
+        writeln!(self.session, "p. This post")?;
+        writeln!(self.session, "a. Its author ({})", post.author_username.as_deref().unwrap_or("unknown"))?;
+        if !comments.is_empty() {
+            writeln!(self.session, "c. A comment")?;
+        }
+        let (target, target_id) = match self.prompt("Report what? (Enter to cancel): ")?.as_deref() {
+            Some("p") => (ReportTarget::Post, post.id.unwrap()),
+            Some("a") => (ReportTarget::User, post.user_id),
+            Some("c") if !comments.is_empty() => {
+                let Some(comment_id) = self.choose_comment(&comments)? else {
+                    return Ok(());
+                };
+                (ReportTarget::Comment, comment_id)
+            }
+            _ => return Ok(()),
+        };
+
+        for (index, reason) in ReportReason::ALL.iter().enumerate() {
+            writeln!(self.session, "{}. {}", index + 1, reason.as_str())?;
+        }
+        let Some(reason) = self
+            .prompt("Reason: ")?
+            .and_then(|r| r.parse::<usize>().ok())
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| ReportReason::ALL.get(i).copied())
+        else {
+            writeln!(self.session, "{}", theme::error("Invalid reason"))?;
+            return Ok(());
+        };
+        let details = self
+            .prompt("Details for the moderators (optional): ")?
+            .filter(|d| !d.is_empty());
+
+        let mut report = Report::new(user.id.unwrap(), target, target_id, reason, details);
+        match self.moderation_manager.file_report(&mut report) {
+            Ok(_) => writeln!(self.session, "{}", theme::success("Thanks, the moderators will review your report"))?,
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error filing report: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    /// Pick one of `comments` by number; None if cancelled or invalid
+    fn choose_comment(&mut self, comments: &[Comment]) -> io::Result<Option<i64>> {
+        let width = layout::width().saturating_sub(8);
+        for (index, comment) in comments.iter().enumerate() {
+            let first_line = comment.content.lines().next().unwrap_or_default();
+            let line = format!("{}: {}", comment.author_username.as_deref().unwrap_or("unknown"), first_line);
+            writeln!(self.session, "{:3}. {}", index + 1, layout::truncate(&line, width))?;
+        }
+        let Some(choice) = self.prompt("Comment number (Enter to cancel): ")? else {
+            return Ok(None);
+        };
+        if choice.is_empty() {
+            return Ok(None);
+        }
+        match choice
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| comments.get(i))
+        {
+            Some(comment) => Ok(comment.id),
+            None => {
+                writeln!(self.session, "{}", theme::error("Invalid selection"))?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Activity noticed by the watcher since this was last called
+    pub(crate) fn take_live_updates(&self) -> Option<LiveUpdates> {
+        self.watcher.as_ref().and_then(|w| w.take_updates())
+    }
+
+    fn show_live_updates(&mut self, user: &User) -> io::Result<()> {
+        let Some(updates) = self.take_live_updates() else {
+            return Ok(());
+        };
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} {}", Icon::Notifications, updates.banner())))?;
+        if updates.warnings > 0 {
+            self.show_pending_warnings(user)?;
+        }
+        Ok(())
+    }
+
+    fn show_pending_warnings(&mut self, user: &User) -> io::Result<()> {
+        match self.moderation_manager.take_unseen_warnings(user.id.unwrap()) {
+            Ok(warnings) => {
+                for warning in warnings {
+                    writeln!(
+                        self.session,
+                        "\n{}",
+                        layout::fill(&theme::warning(format_args!(
+                            "Warning from the moderators ({}): {}",
+                            warning.created_at.format("%Y-%m-%d"),
+                            warning.message
+                        )))
+                    )?;
+                }
+            }
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error fetching warnings: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    fn moderation_queue(&mut self, admin: &User) -> io::Result<()> {
+        loop {
+            writeln!(self.session, "\n{}", theme::heading(format_args!("{} Moderation Queue", Icon::Moderation)))?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+
+            let reports = match self.moderation_manager.get_open_reports() {
+                Ok(reports) => reports,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error fetching reports: {}", e)))?;
+                    return Ok(());
+                }
+            };
+
+            if reports.is_empty() {
+                writeln!(self.session, "{}", theme::success("No open reports."))?;
+            }
+            for (index, report) in reports.iter().enumerate() {
+                writeln!(
+                    self.session,
+                    "{:3}. [{}] {} #{} reported by {} on {}",
+                    index + 1,
+                    report.reason.as_str(),
+                    report.target.as_str(),
+                    report.target_id,
+                    report.reporter_username.as_deref().unwrap_or("unknown"),
+                    report.created_at.format("%Y-%m-%d %H:%M UTC")
+                )?;
+            }
+
+            let Some(choice) =
+                self.prompt("\nEnter a number to review, l for the action log, f for the ranking formula, or Enter to go back: ")?
+            else {
+                return Ok(());
+            };
+            match choice.as_str() {
+                "" => return Ok(()),
+                "l" => {
+                    self.show_moderation_log()?;
+                    continue;
+                }
+                "f" => {
+                    self.edit_ranking_formula()?;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let Some(report) = choice
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| reports.get(i))
+            else {
+                writeln!(self.session, "{}", theme::error("Invalid selection"))?;
+                continue;
+            };
+            self.review_report(admin, report)?;
+        }
+    }
+
+    /// Show the popularity formula and let an admin change each part of it
+    fn edit_ranking_formula(&mut self) -> io::Result<()> {
+        let current = match self.ranking_manager.get_formula() {
+            Ok(formula) => formula,
+            Err(e) => {
+                writeln!(self.session, "{}", theme::error(format_args!("Error fetching the ranking formula: {}", e)))?;
+                return Ok(());
+            }
+        };
+
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Ranking formula", Icon::Ranking)))?;
+        writeln!(
+            self.session,
+            "Score = views {} {} + comments {} {} + bookmarks {} {} + reblogs and quotes {} {}",
+            Icon::Times,
+            current.view_weight,
+            Icon::Times,
+            current.comment_weight,
+            Icon::Times,
+            current.bookmark_weight,
+            Icon::Times,
+            current.reshare_weight
+        )?;
+        writeln!(self.session, "Trending counts the last week, halving every {} hours.", current.half_life_hours)?;
+        writeln!(self.session, "Press Enter to keep a value.")?;
+
+        let ask = |cli: &mut Self, label: &str, value: f64| -> io::Result<Option<f64>> {
+            let Some(input) = cli.prompt(&format!("{} [{}]: ", label, value))? else {
+                return Ok(None);
+            };
+            if input.is_empty() {
+                return Ok(Some(value));
+            }
+            match input.parse::<f64>() {
+                Ok(value) => Ok(Some(value)),
+                Err(_) => {
+                    writeln!(cli.session, "{}", theme::error(format_args!("'{}' is not a number", input)))?;
+                    Ok(None)
+                }
+            }
+        };
+        let Some(view_weight) = ask(self, "View weight", current.view_weight)? else { return Ok(()) };
+        let Some(comment_weight) = ask(self, "Comment weight", current.comment_weight)? else { return Ok(()) };
+        let Some(bookmark_weight) = ask(self, "Bookmark weight", current.bookmark_weight)? else { return Ok(()) };
+        let Some(reshare_weight) = ask(self, "Reblog/quote weight", current.reshare_weight)? else { return Ok(()) };
+        let Some(half_life_hours) = ask(self, "Half-life in hours", current.half_life_hours)? else { return Ok(()) };
+
+        let formula = RankingFormula { view_weight, comment_weight, bookmark_weight, reshare_weight, half_life_hours };
+        if formula == current {
+            return Ok(());
+        }
+        match self.ranking_manager.set_formula(&formula) {
+            Ok(_) => writeln!(self.session, "{}", theme::success("Formula saved and scores recomputed"))?,
+            Err(e) => writeln!(self.session, "{}", theme::error(e))?,
+        }
+        Ok(())
+    }
+
+    fn review_report(&mut self, admin: &User, report: &Report) -> io::Result<()> {
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Report #{}", Icon::Report, report.id.unwrap_or(0))))?;
+        writeln!(self.session, "Reason: {}", report.reason.as_str())?;
+        if let Some(details) = &report.details {
+            writeln!(self.session, "{}", layout::fill(&format!("Details: {}", details)))?;
+        }
+
+        // Show the reported content so the decision is made in context
+        match report.target {
+            ReportTarget::Post => match self.post_manager.get_post(report.target_id) {
+                Ok(Some(post)) => {
+                    if post.hidden_at.is_some() {
+                        writeln!(self.session, "{} Already hidden", Icon::Hidden)?;
+                    }
+                    self.display_post_with_author(admin.id.unwrap(), &post)?;
+                }
+                Ok(None) => writeln!(self.session, "{}", theme::warning("The reported post no longer exists"))?,
+                Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error fetching post: {}", e)))?,
+            },
+            ReportTarget::User => match self.user_manager.find_by_id(report.target_id) {
+                Ok(Some(reported)) => {
+                    writeln!(self.session, "User: {}", reported.username)?;
+                    writeln!(self.session, "Joined: {}", reported.created_at.format("%Y-%m-%d"))?;
+                    writeln!(self.session, "Bio: {}", reported.bio.as_deref().unwrap_or("(not set)"))?;
+                    if reported.suspended_at.is_some() {
+                        writeln!(self.session, "{} Already suspended", Icon::Blocked)?;
+                    }
+                }
+                Ok(None) => writeln!(self.session, "{}", theme::warning("The reported user no longer exists"))?,
+                Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error fetching user: {}", e)))?,
+            },
+            ReportTarget::Comment => match self.comment_manager.get_comment(report.target_id) {
+                Ok(Some(comment)) => {
+                    if comment.hidden_at.is_some() {
+                        writeln!(self.session, "{} Already hidden", Icon::Hidden)?;
+                    }
+                    writeln!(
+                        self.session,
+                        "Comment by {} on post #{} {} {}",
+                        comment.author_username.as_deref().unwrap_or("unknown"),
+                        comment.post_id,
+                        Icon::Separator,
+                        comment.created_at.format("%Y-%m-%d %H:%M UTC")
+                    )?;
+                    writeln!(self.session, "{}", layout::rule('─'))?;
+                    writeln!(self.session, "{}", layout::fill(&comment.content))?;
+                    writeln!(self.session, "{}", layout::rule('─'))?;
+                }
+                Ok(None) => writeln!(self.session, "{}", theme::warning("The reported comment no longer exists"))?,
+                Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error fetching comment: {}", e)))?,
+            },
+        }
+
+        let hideable = report.target != ReportTarget::User;
+        writeln!(self.session, "d. Dismiss")?;
+        if hideable {
+            writeln!(self.session, "h. Hide the {}", report.target.as_str())?;
+        }
+        writeln!(self.session, "w. Warn the author")?;
+        writeln!(self.session, "s. Suspend the author")?;
+        let action = match self.prompt("Choose an action (Enter to go back): ")?.as_deref() {
+            Some("d") => ModerationAction::Dismiss,
+            Some("h") if hideable => ModerationAction::Hide,
+            Some("w") => ModerationAction::Warn,
+            Some("s") => ModerationAction::Suspend,
+            _ => return Ok(()),
+        };
+        let label = if action == ModerationAction::Warn {
+            "Warning message shown to the author (Enter for a default): "
+        } else {
+            "Note for the action log (optional): "
+        };
+        let note = self.prompt(label)?.filter(|n| !n.is_empty());
+
+        match self
+            .moderation_manager
+            .resolve_report(report, admin.id.unwrap(), action, note)
+        {
+            Ok(_) => writeln!(
+                self.session,
+                "{}",
+                theme::success(format_args!(
+                    "Report #{} resolved: {}",
+                    report.id.unwrap_or(0),
+                    action.as_str()
+                ))
+            )?,
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error resolving report: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    fn show_moderation_log(&mut self) -> io::Result<()> {
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Moderation Log", Icon::Log)))?;
+        writeln!(self.session, "{}", layout::rule('='))?;
+        match self.moderation_manager.get_action_log(20) {
+            Ok(entries) if entries.is_empty() => writeln!(self.session, "No moderation actions yet.")?,
+            Ok(entries) => {
+                for entry in entries {
+                    writeln!(
+                        self.session,
+                        "{} {} {} {} #{}{}",
+                        entry.created_at.format("%Y-%m-%d %H:%M UTC"),
+                        entry.admin_username,
+                        entry.action,
+                        entry.target.as_str(),
+                        entry.target_id,
+                        entry.report_id.map(|id| format!(" (report #{})", id)).unwrap_or_default()
+                    )?;
+                    if let Some(note) = &entry.note {
+                        writeln!(self.session, "    {}", note)?;
+                    }
+                }
+            }
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error fetching moderation log: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    fn manage_blocks(&mut self, user: &User) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+
+        loop {
+            writeln!(self.session, "\n{}", theme::heading(format_args!("{} Muted & Blocked Users", Icon::MutedAndBlocked)))?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+
+            match self.block_manager.get_user_blocks(user_id) {
+                Ok(blocks) if blocks.is_empty() => writeln!(self.session, "Nobody is muted or blocked.")?,
+                Ok(blocks) => {
+                    for block in &blocks {
+                        let label = match block.kind {
+                            BlockKind::Mute => format!("{} muted", Icon::Muted),
+                            BlockKind::Block => format!("{} blocked", Icon::Blocked),
+                        };
+                        writeln!(
+                            self.session,
+                            "  {} {} {} since {}",
+                            block.target_username.as_deref().unwrap_or("unknown"),
+                            Icon::Dash,
+                            label,
+                            block.created_at.format("%Y-%m-%d")
+                        )?;
+                    }
+                }
+                Err(e) => {
+                    writeln!(
+                        self.session,
+                        "{}",
+                        theme::error(format_args!("Error fetching muted and blocked users: {}", e))
+                    )?;
+                    return Ok(());
+                }
+            }
+
+            writeln!(self.session, "\nMuted users' posts are hidden from your feeds.")?;
+            writeln!(self.session, "Blocked users are hidden too, and cannot reblog or quote your posts.")?;
+            let Some(input) =
+                self.prompt("m <user> to mute, b <user> to block, u <user> to undo, Enter to go back: ")?
+            else {
+                return Ok(());
+            };
+            if input.is_empty() {
+                return Ok(());
+            }
+
+            let Some((command, username)) = input.split_once(' ') else {
+                writeln!(self.session, "{}", theme::error("Please give a command and a username, e.g. 'm alice'"))?;
+                continue;
+            };
+            let target = match self.user_manager.find_by_username(username.trim()) {
+                Ok(Some(target)) => target,
+                Ok(None) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("User '{}' not found", username.trim())))?;
+                    continue;
+                }
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error looking up user: {}", e)))?;
+                    continue;
+                }
+            };
+            let target_id = target.id.unwrap();
+
+            let result = match command {
+                "m" => self
+                    .block_manager
+                    .set_block(user_id, target_id, BlockKind::Mute)
+                    .map(|_| format!("{} Muted {}", Icon::Muted, target.username)),
+                "b" => self
+                    .block_manager
+                    .set_block(user_id, target_id, BlockKind::Block)
+                    .map(|_| format!("{} Blocked {}", Icon::Blocked, target.username)),
+                "u" => self.block_manager.remove_block(user_id, target_id).map(|removed| {
+                    if removed {
+                        theme::success(format_args!("{} is no longer muted or blocked", target.username))
+                    } else {
+                        format!("{} {} was not muted or blocked", Icon::Info, target.username)
+                    }
+                }),
+                _ => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Unknown command '{}'", command)))?;
+                    continue;
+                }
+            };
+            match result {
+                Ok(message) => writeln!(self.session, "{}", message)?,
+                Err(e) => writeln!(self.session, "{}", theme::error(e))?,
+            }
+        }
+    }
+
+    fn browse_groups(&mut self, user: &User) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+
+        loop {
+            writeln!(self.session, "\n{}", theme::heading(format_args!("{} Groups", Icon::Group)))?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+
+            let groups = match self.group_manager.get_user_groups(user_id) {
+                Ok(groups) => groups,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error fetching groups: {}", e)))?;
+                    return Ok(());
+                }
+            };
+            let invites = self.group_manager.get_pending_invites(user_id).unwrap_or_default();
+
+            if groups.is_empty() {
+                writeln!(self.session, "You are not in any groups yet.")?;
+            }
+            for (index, (group, role)) in groups.iter().enumerate() {
+                writeln!(
+                    self.session,
+                    "{:3}. #{} {} {} ({}, {} member(s))",
+                    index + 1,
+                    group.slug,
+                    Icon::Dash,
+                    group.name,
+                    role.as_str(),
+                    group.member_count
+                )?;
+            }
+            if !invites.is_empty() {
+                writeln!(
+                    self.session,
+                    "\n{}",
+                    theme::heading(format_args!(
+                        "{} {} pending invite(s) {} press i to review",
+                        Icon::Invite,
+                        invites.len(),
+                        Icon::Dash
+                    ))
+                )?;
+            }
+
+            let Some(choice) = self.prompt(
+                "\nEnter a number to open, #name to open any group, c to create, i for invites, Enter to go back: ",
+            )? else {
+                return Ok(());
+            };
+            match choice.as_str() {
+                "" => return Ok(()),
+                "c" => self.create_group(user)?,
+                "i" => self.review_group_invites(user)?,
+                _ if choice.starts_with('#') => match self.group_manager.get_group_by_slug(&choice) {
+                    Ok(Some(group)) => self.view_group(user, &group)?,
+                    Ok(None) => writeln!(self.session, "{}", theme::error(format_args!("No group called {}", choice)))?,
+                    Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error fetching group: {}", e)))?,
+                },
+                _ => match choice
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|i| groups.get(i))
+                {
+                    Some((group, _)) => self.view_group(user, group)?,
+                    None => writeln!(self.session, "{}", theme::error("Invalid selection"))?,
+                },
+            }
+        }
+    }
+
+    fn create_group(&mut self, user: &User) -> io::Result<()> {
+        let Some(slug) = self.prompt("Group handle (e.g. platform-team): ")? else {
+            return Ok(());
+        };
+        let slug = slug.trim_start_matches('#').to_string();
+        if let Err(e) = GroupManager::validate_slug(&slug) {
+            writeln!(self.session, "{}", theme::error(e))?;
+            return Ok(());
+        }
+        let Some(name) = self.prompt("Display name: ")?.filter(|n| !n.is_empty()) else {
+            writeln!(self.session, "{}", theme::error("Display name cannot be empty"))?;
+            return Ok(());
+        };
+        let description = self
+            .prompt("Description (optional): ")?
+            .filter(|d| !d.is_empty());
+
+        let mut group = Group::new(slug, name, description);
+        match self.group_manager.create_group(&mut group, user.id.unwrap()) {
+            Ok(_) => writeln!(
+                self.session,
+                "{}",
+                theme::success(format_args!("Created #{} {} you are its owner", group.slug, Icon::Dash))
+            )?,
+            Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error creating group: {}", e)))?,
+        }
+        Ok(())
+    }
+
+    fn review_group_invites(&mut self, user: &User) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+        let invites = match self.group_manager.get_pending_invites(user_id) {
+            Ok(invites) => invites,
+            Err(e) => {
+                writeln!(self.session, "{}", theme::error(format_args!("Error fetching invites: {}", e)))?;
+                return Ok(());
+            }
+        };
+        if invites.is_empty() {
+            writeln!(self.session, "No pending invites.")?;
+            return Ok(());
+        }
+
+        for invite in &invites {
+            writeln!(
+                self.session,
+                "\n{}",
+                theme::heading(format_args!(
+                    "{} {} invited you to #{} on {}",
+                    Icon::Invite,
+                    invite.invited_by,
+                    invite.group_slug,
+                    invite.created_at.format("%Y-%m-%d")
+                ))
+            )?;
+            let accept = match self.prompt("a. Accept  d. Decline  Enter. Decide later: ")?.as_deref() {
+                Some("a") => true,
+                Some("d") => false,
+                _ => continue,
+            };
+            match self.group_manager.respond_to_invite(invite, user_id, accept) {
+                Ok(_) if accept => writeln!(
+                    self.session,
+                    "{}",
+                    theme::success(format_args!("You joined #{}", invite.group_slug))
+                )?,
+                Ok(_) => writeln!(self.session, "Declined the invite to #{}", invite.group_slug)?,
+                Err(e) => writeln!(self.session, "{}", theme::error(e))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn view_group(&mut self, user: &User, group: &Group) -> io::Result<()> {
+        let user_id = user.id.unwrap();
+        let group_id = group.id.unwrap();
+        let mut request = PageRequest::First;
+
+        loop {
+            writeln!(
+                self.session,
+                "\n{}",
+                theme::heading(format_args!("{} #{} {} {}", Icon::Group, group.slug, Icon::Dash, group.name))
+            )?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+            if let Some(description) = &group.description {
+                writeln!(self.session, "{}", description)?;
+            }
+
+            let role = self.group_manager.get_role(group_id, user_id).unwrap_or(None);
+            match self.group_manager.get_members(group_id) {
+                Ok(members) => {
+                    let names: Vec<String> = members
+                        .iter()
+                        .map(|m| format!("{} ({})", m.username, m.role.as_str()))
+                        .collect();
+                    writeln!(self.session, "Members: {}", names.join(", "))?;
+                }
+                Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error fetching members: {}", e)))?,
+            }
+
+            let (mut next, mut prev) = (None, None);
+            match self.post_manager.get_group_posts(group_id, user_id, request, PAGE_SIZE) {
+                Ok(page) if page.items.is_empty() => writeln!(self.session, "\n{} No posts in this group yet.", Icon::Post)?,
+                Ok(page) => {
+                    for post in &page.items {
+                        writeln!(self.session)?;
+                        self.display_post_with_author(user_id, post)?;
+                    }
+                    (next, prev) = (page.next, page.prev);
+                }
+                Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error fetching posts: {}", e)))?,
+            }
+
+            writeln!(self.session)?;
+            if next.is_some() {
+                writeln!(self.session, "n. Older posts")?;
+            }
+            if prev.is_some() {
+                writeln!(self.session, "p. Newer posts")?;
+            }
+            match role {
+                Some(role) => {
+                    writeln!(self.session, "w. Write a post for #{}", group.slug)?;
+                    if role.can_invite() {
+                        writeln!(self.session, "i <user>. Invite someone")?;
+                    }
+                    if role.can_manage() {
+                        writeln!(self.session, "r <user> <owner|editor|member>. Change a member's role")?;
+                    }
+                    writeln!(self.session, "l. Leave the group")?;
+                }
+                None if next.is_none() && prev.is_none() => {
+                    writeln!(self.session, "You are not a member of this group.")?;
+                    return Ok(());
+                }
+                None => writeln!(self.session, "You are not a member of this group.")?,
+            }
+            let Some(choice) = self.prompt("Choose an action (Enter to go back): ")? else {
+                return Ok(());
+            };
+
+            match (choice.as_str(), next, prev) {
+                ("n", Some(cursor), _) => {
+                    request = PageRequest::After(cursor);
+                    continue;
+                }
+                ("p", _, Some(cursor)) => {
+                    request = PageRequest::Before(cursor);
+                    continue;
+                }
+                _ if role.is_none() => return Ok(()),
+                _ => {}
+            }
+
+            let mut parts = choice.split_whitespace();
+            match (parts.next(), parts.next(), parts.next()) {
+                (None, _, _) => return Ok(()),
+                (Some("w"), None, None) => self.create_post_in(user, Some(group))?,
+                (Some("i"), Some(username), None) => match self.user_manager.find_by_username(username) {
+                    Ok(Some(invitee)) => {
+                        match self.group_manager.invite(group_id, user_id, invitee.id.unwrap()) {
+                            Ok(_) => writeln!(
+                                self.session,
+                                "{} Invited {} to #{}",
+                                Icon::Invite,
+                                invitee.username,
+                                group.slug
+                            )?,
+                            Err(e) => writeln!(self.session, "{}", theme::error(e))?,
+                        }
+                    }
+                    Ok(None) => writeln!(self.session, "{}", theme::error(format_args!("User '{}' not found", username)))?,
+                    Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error looking up user: {}", e)))?,
+                },
+                (Some("r"), Some(username), Some(new_role)) => {
+                    let Some(new_role) = GroupRole::parse(new_role) else {
+                        writeln!(self.session, "{}", theme::error("Roles are owner, editor or member"))?;
+                        continue;
+                    };
+                    match self.user_manager.find_by_username(username) {
+                        Ok(Some(member)) => {
+                            match self.group_manager.set_role(group_id, user_id, member.id.unwrap(), new_role) {
+                                Ok(_) => writeln!(
+                                    self.session,
+                                    "{}",
+                                    theme::success(format_args!(
+                                        "{} is now {}",
+                                        member.username,
+                                        new_role.as_str()
+                                    ))
+                                )?,
+                                Err(e) => writeln!(self.session, "{}", theme::error(e))?,
+                            }
+                        }
+                        Ok(None) => writeln!(
+                            self.session,
+                            "{}",
+                            theme::error(format_args!("User '{}' not found", username))
+                        )?,
+                        Err(e) => writeln!(self.session, "{}", theme::error(format_args!("Error looking up user: {}", e)))?,
+                    }
+                }
+                (Some("l"), None, None) => match self.group_manager.leave(group_id, user_id) {
+                    Ok(_) => {
+                        writeln!(self.session, "{} You left #{}", Icon::Left, group.slug)?;
+                        return Ok(());
+                    }
+                    Err(e) => writeln!(self.session, "{}", theme::error(e))?,
+                },
+                _ => writeln!(self.session, "{}", theme::error("Invalid action"))?,
+            }
+        }
+    }
+
+    fn browse_people(&mut self, viewer: &User) -> io::Result<()> {
+        let mut search: Option<String> = None;
+
+        loop {
+            writeln!(self.session, "\n{}", theme::heading(format_args!("{} People", Icon::Group)))?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+            if let Some(term) = &search {
+                writeln!(self.session, "{} Names containing '{}'", Icon::Find, term)?;
+            }
+
+            let people = match self.user_manager.list_users(search.as_deref()) {
+                Ok(people) => people,
+                Err(e) => {
+                    writeln!(self.session, "{}", theme::error(format_args!("Error fetching users: {}", e)))?;
+                    return Ok(());
+                }
+            };
+
+            if people.is_empty() {
+                writeln!(self.session, "Nobody matches that name.")?;
+            }
+            for (index, summary) in people.iter().enumerate() {
+                writeln!(
+                    self.session,
+                    "{:3}. {} {} joined {}, {} post(s)",
+                    index + 1,
+                    summary.user.display_label(),
+                    Icon::Dash,
+                    summary.user.created_at.format("%Y-%m-%d"),
+                    summary.post_count
+                )?;
+                if let Some(bio) = &summary.user.bio {
+                    writeln!(self.session, "{}", layout::indent("     ", bio))?;
+                }
+            }
+
+            let Some(choice) = self.prompt(
+                "\nEnter a number to open a profile, /name to search, / to clear, or Enter to go back: ",
+            )? else {
+                return Ok(());
+            };
+            if choice.is_empty() {
+                return Ok(());
+            }
+            if let Some(term) = choice.strip_prefix('/') {
+                let term = term.trim();
+                search = if term.is_empty() { None } else { Some(term.to_string()) };
+                continue;
+            }
+
+            match choice
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| people.get(i))
+            {
+                Some(summary) => self.view_user_page(viewer, &summary.user)?,
+                None => writeln!(self.session, "{}", theme::error("Invalid selection"))?,
+            }
+        }
+    }
+
+    fn view_user_page(&mut self, viewer: &User, user: &User) -> io::Result<()> {
+        self.show_profile(user)?;
+
+        let Some(choice) = self.prompt("\np. View their posts, a. Browse their archive, Enter to go back: ")? else {
+            return Ok(());
+        };
+        match choice.as_str() {
+            "p" => {}
+            "a" => {
+                self.browse_archive(viewer, Some(user))?;
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Posts by {}", Icon::Posts, user.display_label())))?;
+        writeln!(self.session, "{}", layout::rule('='))?;
+        let author_id = user.id.unwrap();
+        let shown = self.browse_posts(
+            |cli, request| cli.post_manager.get_posts_by_author(author_id, request, PAGE_SIZE),
+            |cli, number, post| {
+                writeln!(cli.session, "\n{}", theme::heading(format_args!("{} Post #{}", Icon::Page, number)))?;
+                cli.display_post_with_author(viewer.id.unwrap(), post)
+            },
+            "",
+            |_, _, _| Ok(false),
+        )?;
+        match shown {
+            Some(false) => writeln!(self.session, "{} {} has not posted yet.", Icon::Post, user.username)?,
+            Some(true) => writeln!(
+                self.session,
+                "{}",
+                theme::tip("Use 'Read a post' with a Post ID to bookmark, reblog or report it.")
+            )?,
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Returns true when the account was deleted and the session should end
+    fn profile_screen(&mut self, user: &mut User) -> io::Result<bool> {
+        self.show_profile(user)?;
+
+        writeln!(self.session, "\ne. Edit profile")?;
+        writeln!(self.session, "d. Download my data")?;
+        writeln!(self.session, "x. Delete my account")?;
+        let Some(choice) = self.prompt("Choose an action (Enter to go back): ")? else {
+            return Ok(false);
+        };
+        match choice.as_str() {
+            "e" => self.edit_profile(user)?,
+            "d" => self.download_my_data(user)?,
+            "x" => return self.delete_account(user),
+            "" => {}
+            _ => writeln!(self.session, "{}", theme::error("Invalid action"))?,
+        }
+        Ok(false)
+    }
+
+    fn download_my_data(&mut self, user: &User) -> io::Result<()> {
+        match self.account_manager.export(user) {
+            Ok(export) => {
+                writeln!(self.session, "\n{}", theme::heading(format_args!("{} Your archive contains:", Icon::Export)))?;
+                writeln!(
+                    self.session,
+                    "  {} profile, SSH key and {} former username(s)",
+                    Icon::Bullet,
+                    export.former_usernames.len()
+                )?;
+                writeln!(
+                    self.session,
+                    "  {} {} post(s) and {} comment(s)",
+                    Icon::Bullet,
+                    export.posts.len(),
+                    export.comments.len()
+                )?;
+                writeln!(
+                    self.session,
+                    "  {} {} poll vote(s), {} bookmark(s), {} mute/block entries",
+                    Icon::Bullet,
+                    export.poll_votes.len(),
+                    export.bookmarks.len(),
+                    export.blocks.len()
+                )?;
+                writeln!(
+                    self.session,
+                    "  {} {} group membership(s), {} saved search(es) and {} notification(s)",
+                    Icon::Bullet,
+                    export.groups.len(),
+                    export.saved_searches.len(),
+                    export.notifications.len()
+                )?;
+            }
+            Err(e) => {
+                writeln!(self.session, "{}", theme::error(format_args!("Error collecting your data: {}", e)))?;
+                return Ok(());
+            }
+        }
+        writeln!(self.session, "\nThis terminal can't receive files, so download it from your own shell:")?;
+        writeln!(self.session, "  ssh {}@<blog host> export > my-blog-data.tar", user.login_name)?;
+        writeln!(self.session, "It is a tar archive with account.json plus your posts and comments as Markdown.")?;
+        Ok(())
+    }
+
+    /// Returns true once the account is gone
+    fn delete_account(&mut self, user: &User) -> io::Result<bool> {
+        writeln!(self.session, "\n{}", theme::warning("Deleting your account is permanent. This removes:"))?;
+        writeln!(self.session, "  {} your profile, SSH key and former usernames", Icon::Bullet)?;
+        writeln!(self.session, "  {} all your posts, with their polls and comments", Icon::Bullet)?;
+        writeln!(self.session, "  {} your comments, votes, bookmarks, mutes, blocks and notifications", Icon::Bullet)?;
+        writeln!(self.session, "Reshares of your posts will show the original as unavailable. Groups you")?;
+        writeln!(self.session, "own alone pass to their longest-serving editor or member. Moderation")?;
+        writeln!(self.session, "history is kept without your name.")?;
+        writeln!(self.session, "{}", theme::tip("Choose 'Download my data' first if you want a copy."))?;
+
+        let Some(confirmation) = self.prompt(&format!("\nType your username ({}) to confirm: ", user.username))? else {
+            return Ok(false);
+        };
+        if confirmation != user.username {
+            writeln!(self.session, "{}", theme::error("Username did not match; nothing was deleted"))?;
+            return Ok(false);
+        }
+
+        match self.account_manager.delete_account(user.id.unwrap()) {
+            Ok(_) => {
+                writeln!(self.session, "{}", theme::success("Your account has been deleted. Goodbye!"))?;
+                Ok(true)
+            }
+            Err(e) => {
+                writeln!(self.session, "{}", theme::error(format_args!("Error deleting account: {}", e)))?;
+                Ok(false)
+            }
+        }
+    }
+
+    fn show_profile(&mut self, user: &User) -> io::Result<()> {
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Profile Information", Icon::Person)))?;
+        writeln!(self.session, "{}", layout::rule('='))?;
+        if let Some(avatar) = &user.avatar {
+            for line in avatar.lines() {
+                writeln!(self.session, "  {}", line)?;
+            }
+            writeln!(self.session)?;
+        }
+        writeln!(self.session, "Username: {}", user.username)?;
+        if let Ok(aliases) = self.user_manager.get_aliases(user.id.unwrap())
+            && !aliases.is_empty()
+        {
+            writeln!(self.session, "Formerly known as: {}", aliases.join(", "))?;
+        }
+        if let Some(name) = &user.display_name {
+            writeln!(self.session, "Display name: {}", name)?;
+        }
+        if let Some(pronouns) = &user.pronouns {
+            writeln!(self.session, "Pronouns: {}", pronouns)?;
+        }
+        writeln!(self.session, "User ID: {}", user.id.unwrap_or(0))?;
+        writeln!(self.session, "Joined: {}", user.created_at.format("%Y-%m-%d %H:%M UTC"))?;
+        
+        if let Some(bio) = &user.bio {
+            writeln!(self.session, "{}", layout::fill(&format!("Bio: {}", bio)))?;
+        } else {
+            writeln!(self.session, "Bio: (not set)")?;
+        }
+
+        if let Some(tz) = user.timezone.as_deref().and_then(|tz| tz.parse::<Tz>().ok()) {
+            writeln!(self.session, "Time zone: {} (local time {})", tz, Utc::now().with_timezone(&tz).format("%H:%M"))?;
+        }
+        for link in &user.links {
+            writeln!(self.session, "{} {}", Icon::Link, link)?;
+        }
+
+        // Show post count, as the directory counts it
+        match self.post_manager.count_posts_by_author(user.id.unwrap()) {
+            Ok(count) => {
+                writeln!(self.session, "Total posts: {}", count)?;
+            }
+            Err(_) => {
+                writeln!(self.session, "Total posts: (error fetching)")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Edit one field at a time; each change is validated and saved straight away
+    fn edit_profile(&mut self, user: &mut User) -> io::Result<()> {
+        loop {
+            writeln!(self.session, "\n{}", theme::heading(format_args!("{} Edit Profile", Icon::Edit)))?;
+            writeln!(self.session, "{}", layout::rule('='))?;
+            writeln!(self.session, "1. Display name: {}", user.display_name.as_deref().unwrap_or("(not set)"))?;
+            writeln!(self.session, "2. Bio: {}", user.bio.as_deref().unwrap_or("(not set)"))?;
+            writeln!(self.session, "3. Pronouns: {}", user.pronouns.as_deref().unwrap_or("(not set)"))?;
+            writeln!(self.session, "4. Time zone: {}", user.timezone.as_deref().unwrap_or("(not set)"))?;
+            writeln!(self.session, "5. Links: {}", user.links.len())?;
+            writeln!(
+                self.session,
+                "6. ASCII avatar: {}",
+                if user.avatar.is_some() { "set" } else { "(not set)" }
+            )?;
+            writeln!(self.session, "7. Username: {}", user.username)?;
+            writeln!(self.session, "8. Theme: {}", user.theme.as_str())?;
+
+            let Some(choice) = self.prompt("\nChoose a field to edit (Enter to go back): ")? else {
+                return Ok(());
+            };
+
+            let mut updated = user.clone();
+            match choice.as_str() {
+                "" => return Ok(()),
+                "1" => updated.display_name = self.prompt_field("Display name")?,
+                "2" => updated.bio = self.prompt_field("Bio")?,
+                "3" => updated.pronouns = self.prompt_field("Pronouns (e.g. she/her)")?,
+                "4" => updated.timezone = self.prompt_field("Time zone (e.g. Europe/Berlin)")?,
+                "5" => {
+                    writeln!(
+                        self.session,
+                        "Enter up to {} links, one per line (end with a line containing only '.'):",
+                        User::MAX_LINKS
+                    )?;
+                    updated.links = self
+                        .read_content()?
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                }
+                "6" => {
+                    writeln!(
+                        self.session,
+                        "Draw your avatar, at most {} lines of {} characters (end with a line containing only '.'):",
+                        User::AVATAR_MAX_LINES,
+                        User::AVATAR_MAX_WIDTH
+                    )?;
+                    let art = self.read_content()?;
+                    let art = art.trim_end();
+                    updated.avatar = if art.trim().is_empty() { None } else { Some(art.to_string()) };
+                }
+                "7" => {
+                    self.rename_account(user)?;
+                    continue;
+                }
+                "8" => {
+                    for (index, option) in Theme::ALL.iter().enumerate() {
+                        writeln!(self.session, "{}. {} {} {}", index + 1, option.as_str(), Icon::Dash, option.description())?;
+                    }
+                    let Some(chosen) = self
+                        .prompt("Theme: ")?
+                        .and_then(|t| t.parse::<usize>().ok())
+                        .and_then(|n| n.checked_sub(1))
+                        .and_then(|i| Theme::ALL.get(i).copied())
+                    else {
+                        writeln!(self.session, "{}", theme::error("Invalid selection"))?;
+                        continue;
+                    };
+                    updated.theme = chosen;
+                }
+                _ => {
+                    writeln!(self.session, "{}", theme::error("Invalid option"))?;
+                    continue;
+                }
+            }
+
+            match self.user_manager.update_profile(&updated) {
+                Ok(_) => {
+                    *user = updated;
+                    // The terminal may not be able to show the theme just chosen
+                    let shown = theme::apply(user.theme);
+                    writeln!(self.session, "{}", theme::success("Profile updated"))?;
+                    if shown != user.theme {
+                        writeln!(
+                            self.session,
+                            "{}",
+                            theme::tip(format_args!(
+                                "This terminal shows the {} theme instead (NO_COLOR, TERM=dumb or no colour support)",
+                                shown.as_str()
+                            ))
+                        )?;
+                    }
+                }
+                Err(e) => writeln!(self.session, "{}", theme::error(e))?,
+            }
+        }
+    }
+
+    fn rename_account(&mut self, user: &mut User) -> io::Result<()> {
+        writeln!(self.session, "Your old username will keep pointing at your profile and can't be taken by anyone else.")?;
+        let Some(new_username) = self.prompt("New username (Enter to cancel): ")?.filter(|n| !n.is_empty()) else {
+            return Ok(());
+        };
+
+        match self.user_manager.rename_user(user, &new_username) {
+            Ok(_) => {
+                writeln!(self.session, "{}", theme::success(format_args!("You are now {}", user.username)))?;
+                writeln!(self.session, "{} You still log in over SSH as {}", Icon::Key, user.login_name)?;
+            }
+            Err(e) => writeln!(self.session, "{}", theme::error(e))?,
+        }
+        Ok(())
+    }
+
+    /// Prompt for a single-line profile field; an empty answer clears it
+    fn prompt_field(&mut self, label: &str) -> io::Result<Option<String>> {
+        Ok(self.prompt(&format!("{} (Enter to clear): ", label))?.filter(|value| !value.is_empty()))
+    }
+
+    fn display_post(&mut self, viewer_id: i64, post: &Post) -> io::Result<()> {
+        // Reshares need the attribution and embed that only the full view renders
+        if post.kind != PostKind::Post {
+            self.display_post_with_author(viewer_id, post)?;
+            return Ok(());
+        }
+
+        writeln!(self.session, "{}", layout::rule('─'))?;
+        writeln!(self.session, "{} {}", Icon::Post, post.title)?;
+        writeln!(self.session, "{} Post ID: {}", Icon::Number, post.id.unwrap_or(0))?;
+        if post.hidden_at.is_some() {
+            writeln!(self.session, "{} Hidden by moderators", Icon::Hidden)?;
+        }
+        if let Some(slug) = &post.group_slug {
+            writeln!(self.session, "{} Group: #{}", Icon::Group, slug)?;
+        }
+        writeln!(self.session, "{} Created: {}", Icon::Date, post.created_at.format("%Y-%m-%d %H:%M UTC"))?;
+        if post.updated_at != post.created_at {
+            writeln!(self.session, "{} Updated: {}", Icon::Post, post.updated_at.format("%Y-%m-%d %H:%M UTC"))?;
+        }
+        writeln!(self.session, "{}", layout::rule('─'))?;
+        
+        self.display_content(post)?;
+
+        if let Ok(Some(poll)) = self.poll_manager.get_poll_for_post(post.id.unwrap_or(0)) {
+            self.display_poll(&poll)?;
+        }
+        
+        writeln!(self.session, "{}", layout::rule('─'))?;
+        Ok(())
+    }
+
+    fn display_post_with_author(&mut self, viewer_id: i64, post: &Post) -> io::Result<()> {
+        if post.kind == PostKind::Reblog {
+            writeln!(self.session, "{}", layout::rule('─'))?;
+            writeln!(
+                self.session,
+                "{} {} reblogged (Post ID: {})",
+                Icon::Reblog,
+                post.author_username.as_deref().unwrap_or("unknown"),
+                post.id.unwrap_or(0)
+            )?;
+            match self.load_original(post) {
+                Some(original) => self.display_post_with_author(viewer_id, &original)?,
+                None => {
+                    writeln!(self.session, "{}", theme::warning("original unavailable"))?;
+                    writeln!(self.session, "{}", layout::rule('─'))?;
+                }
+            }
+            return Ok(());
+        }
+
+        writeln!(self.session, "{}", layout::rule('─'))?;
+        writeln!(self.session, "{} {}", Icon::Post, post.title)?;
+        writeln!(self.session, "{} Post ID: {}", Icon::Number, post.id.unwrap_or(0))?;
+        if let Some(slug) = &post.group_slug {
+            writeln!(self.session, "{} Group: #{}", Icon::Group, slug)?;
+        }
+        if let Some(username) = &post.author_username {
+            let label = author_label(username, post.author_display_name.as_deref());
+            match &post.author_pronouns {
+                Some(pronouns) => writeln!(
+                    self.session,
+                    "{} Author: {} {} {}",
+                    Icon::Person,
+                    label,
+                    Icon::Separator,
+                    pronouns
+                )?,
+                None => writeln!(self.session, "{} Author: {}", Icon::Person, label)?,
+            }
+        } else {
+            writeln!(self.session, "{} Author ID: {}", Icon::Person, post.user_id)?;
+        }
+        writeln!(self.session, "{} Created: {}", Icon::Date, post.created_at.format("%Y-%m-%d %H:%M UTC"))?;
+        if post.updated_at != post.created_at {
+            writeln!(self.session, "{} Updated: {}", Icon::Post, post.updated_at.format("%Y-%m-%d %H:%M UTC"))?;
+        }
+        writeln!(self.session, "{}", layout::rule('─'))?;
+        
+        self.display_content(post)?;
+
+        if let Ok(Some(poll)) = self.poll_manager.get_poll_for_post(post.id.unwrap_or(0)) {
+            self.display_poll(&poll)?;
+        }
+
+        if post.kind == PostKind::Quote {
+            self.display_quote_embed(post)?;
+        }
+
+        self.display_related(viewer_id, post)?;
+        
+        writeln!(self.session, "{}", layout::rule('─'))?;
+        Ok(())
+    }
+
+    /// Up to five posts like this one, to read next
+    fn display_related(&mut self, viewer_id: i64, post: &Post) -> io::Result<()> {
+        let related = match self.related_manager.get_related(post, viewer_id, 5) {
+            Ok(related) => related,
+            Err(e) => {
+                writeln!(self.session, "{}", theme::error(format_args!("Error finding related posts: {}", e)))?;
+                return Ok(());
+            }
+        };
+        if related.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(self.session, "\n{}", theme::heading(format_args!("{} Related posts", Icon::Link)))?;
+        for other in &related {
+            writeln!(
+                self.session,
+                "  {} {} by {} (Post ID: {})",
+                Icon::Bullet,
+                other.title,
+                other.author_username.as_deref().unwrap_or("unknown"),
+                other.id.unwrap_or(0)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Post bodies longer than the screen open in the built-in pager
+    fn display_content(&mut self, post: &Post) -> io::Result<()> {
+        if let Err(e) = pager::page(&mut self.session, &post.title, &post.content) {
+            writeln!(
+                self.session,
+                "{}",
+                theme::warning(format_args!("Pager unavailable ({}), showing the post as text", e))
+            )?;
+            writeln!(self.session, "{}", layout::fill(&post.content))?;
+        }
+        Ok(())
+    }
+
+    fn display_comments(&mut self, user: &User, post_id: i64) -> io::Result<()> {
+        let comments = match self.comment_manager.get_post_comments(post_id, user.id.unwrap()) {
+            Ok(comments) => comments,
+            Err(e) => {
+                writeln!(self.session, "{}", theme::error(format_args!("Error fetching comments: {}", e)))?;
+                return Ok(());
+            }
+        };
+        if comments.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(self.session, "{} Comments ({})", Icon::Comment, comments.len())?;
+        for comment in &comments {
+            writeln!(self.session, "{}", layout::rule('─'))?;
+            writeln!(
+                self.session,
+                "{} {} {}",
+                comment.author_username.as_deref().unwrap_or("unknown"),
+                Icon::Separator,
+                comment.created_at.format("%Y-%m-%d %H:%M UTC")
+            )?;
+            writeln!(self.session, "{}", layout::fill(&comment.content))?;
+        }
+        writeln!(self.session, "{}", layout::rule('─'))?;
+        Ok(())
+    }
+
+    /// Question, status and an ASCII bar chart of the current tallies
+    fn display_poll(&mut self, poll: &Poll) -> io::Result<()> {
+        const MAX_BAR_WIDTH: usize = 20;
+
+        writeln!(self.session)?;
+        let kind = if poll.multiple_choice { "multiple choice" } else { "single choice" };
+        writeln!(self.session, "{} Poll: {} ({})", Icon::Poll, poll.question, kind)?;
+        match poll.closes_at {
+            Some(closes_at) if poll.is_closed() => {
+                writeln!(self.session, "   Closed {} {} final results", closes_at.format("%Y-%m-%d %H:%M UTC"), Icon::Dash)?
+            }
+            Some(closes_at) => writeln!(self.session, "   Closes {}", closes_at.format("%Y-%m-%d %H:%M UTC"))?,
+            None => {}
+        }
+
+        let total = poll.total_votes();
+        // Labels get at most a third of the line; the bar shrinks to fit the
+        // rest beside the number, the tally and the percentage
+        let label_width = poll
+            .options
+            .iter()
+            .map(|o| layout::display_width(&o.label))
+            .max()
+            .unwrap_or(0)
+            .min(layout::width() / 3);
+        let bar_width = layout::width()
+            .saturating_sub(label_width + 22)
+            .clamp(5, MAX_BAR_WIDTH);
+        for (index, option) in poll.options.iter().enumerate() {
+            let filled = if total > 0 {
+                (option.votes as usize * bar_width).div_ceil(total as usize)
+            } else {
+                0
+            };
+            let percent = if total > 0 { option.votes * 100 / total } else { 0 };
+            writeln!(
+                self.session,
+                "  {:2}. {} [{}{}] {} ({}%)",
+                index + 1,
+                layout::pad(&layout::truncate(&option.label, label_width), label_width),
+                "#".repeat(filled),
+                ".".repeat(bar_width - filled),
+                option.votes,
+                percent
+            )?;
+        }
+        writeln!(self.session, "   {} vote(s)", total)?;
+        Ok(())
+    }
+
+    /// Short preview of the post a quote refers to
+    fn display_quote_embed(&mut self, post: &Post) -> io::Result<()> {
+        writeln!(self.session)?;
+        match self.load_original(post) {
+            Some(original) => {
+                writeln!(
+                    self.session,
+                    "  {} {} Quoting {} (Post ID: {})",
+                    Icon::FrameTop,
+                    Icon::Comment,
+                    original.author_username.as_deref().unwrap_or("unknown"),
+                    original.id.unwrap_or(0)
+                )?;
+                writeln!(
+                    self.session,
+                    "{}",
+                    layout::truncate(&format!("  {} {}", Icon::FrameSide, original.title), layout::width())
+                )?;
+                let lines = layout::wrap(&original.content, layout::width().saturating_sub(4));
+                for line in lines.iter().take(3) {
+                    writeln!(self.session, "  {} {}", Icon::FrameSide, line)?;
+                }
+                if lines.len() > 3 {
+                    writeln!(self.session, "  {} {}", Icon::FrameSide, Icon::Ellipsis)?;
+                }
+            }
+            None => {
+                writeln!(self.session, "  {} {} Quoting", Icon::FrameTop, Icon::Comment)?;
+                writeln!(self.session, "  {} original unavailable", Icon::FrameSide)?;
+            }
+        }
+        writeln!(self.session, "  {}", Icon::FrameBottom)?;
+        Ok(())
+    }
+
+    /// Print a prompt and read one trimmed line; None once input has ended
+    fn prompt(&mut self, label: &str) -> io::Result<Option<String>> {
+        // Lay out for the new size once the window has been resized
+        if layout::take_resize() {
+            session::fit_output(&self.session);
+        }
+        write!(self.session, "{}", label)?;
+        self.session.flush()?;
+
+        let mut input = String::new();
+        if self.session.read_line(&mut input)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(input.trim().to_string()))
+    }
+
+    /// Read post content until a line containing only '.' (or end of input)
+    fn read_content(&mut self) -> io::Result<String> {
+        let mut content = String::new();
+        let mut line_count = 0;
+
+        loop {
+            let mut line = String::new();
+            if self.session.read_line(&mut line)? == 0 {
+                break;
+            }
+            if line.trim() == "." {
+                break;
+            }
+            content.push_str(&line);
+            line_count += 1;
+
+            // Show progress for longer posts
+            if line_count % 10 == 0 {
+                writeln!(self.session, "({} lines written...)", line_count)?;
+            }
+        }
+        Ok(content)
     }
 }
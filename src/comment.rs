@@ -0,0 +1,215 @@
+// src/comment.rs
+
+use crate::models::Comment;
+use crate::database::Database;
+use crate::notification::NotificationManager;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+
+/// Users following a post's comments: everyone with an active subscription,
+/// plus the author unless they opted out. Bind the post as `?1`.
+const SUBSCRIBERS: &str =
+    "SELECT user_id FROM comment_subscriptions WHERE post_id = ?1 AND subscribed = 1
+     UNION
+     SELECT p.user_id FROM posts p
+     WHERE p.id = ?1
+       AND NOT EXISTS (
+           SELECT 1 FROM comment_subscriptions s
+           WHERE s.post_id = p.id AND s.user_id = p.user_id AND s.subscribed = 0
+       )";
+
+/// Comment storage together with thread subscriptions and their fan-out, so
+/// every front end that adds comments notifies subscribers the same way.
+pub struct CommentManager {
+    db: Database,
+}
+
+impl CommentManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    fn comment_from_row(row: &Row) -> rusqlite::Result<Comment> {
+        Ok(Comment {
+            id: Some(row.get(0)?),
+            post_id: row.get(1)?,
+            user_id: row.get(2)?,
+            content: row.get(3)?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            author_username: Some(row.get(5)?),
+            hidden_at: row
+                .get::<_, Option<String>>(6)?
+                .map(|s| DateTime::parse_from_rfc3339(&s).unwrap().with_timezone(&Utc)),
+        })
+    }
+
+    /// Store a comment, subscribe its author to the thread and notify the
+    /// other subscribers, all in one transaction
+    pub fn add_comment(&self, comment: &mut Comment) -> Result<()> {
+        if comment.content.trim().is_empty() {
+            return Err(Error::msg("Comment cannot be empty"));
+        }
+
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let post: Option<(String, bool, bool)> = tx
+            .query_row(
+                "SELECT p.title, p.hidden_at IS NOT NULL, EXISTS (
+                     SELECT 1 FROM user_blocks b
+                     WHERE b.user_id = p.user_id AND b.target_user_id = ?2 AND b.kind = 'block'
+                 )
+                 FROM posts p WHERE p.id = ?1",
+                params![comment.post_id, comment.user_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let Some((post_title, hidden, blocked)) = post else {
+            return Err(Error::msg("Post not found"));
+        };
+        if hidden {
+            return Err(Error::msg("This post has been hidden by the moderators"));
+        }
+        if blocked {
+            return Err(Error::msg("The author of this post has blocked you"));
+        }
+
+        tx.execute(
+            "INSERT INTO comments (post_id, user_id, content, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![comment.post_id, comment.user_id, comment.content, comment.created_at.to_rfc3339()],
+        )?;
+        comment.id = Some(tx.last_insert_rowid());
+
+        Self::write_subscription(&tx, comment.user_id, comment.post_id, true)?;
+
+        let commenter: String = tx.query_row(
+            "SELECT username FROM users WHERE id = ?1",
+            params![comment.user_id],
+            |row| row.get(0),
+        )?;
+        let message = format!("{} commented on '{}'", commenter, post_title);
+
+        // Nobody hears from people they muted or blocked
+        let mut stmt = tx.prepare(&format!(
+            "SELECT user_id FROM ({})
+             WHERE user_id != ?2
+               AND user_id NOT IN (SELECT user_id FROM user_blocks WHERE target_user_id = ?2)",
+            SUBSCRIBERS
+        ))?;
+        let recipients: Vec<i64> = stmt
+            .query_map(params![comment.post_id, comment.user_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+        for recipient in recipients {
+            NotificationManager::notify(
+                &tx,
+                recipient,
+                "comment",
+                Some(comment.post_id),
+                Some(comment.user_id),
+                &message,
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Comments on a post, oldest first, minus those hidden by moderators and
+    /// those by people the viewer muted or blocked
+    pub fn get_post_comments(&self, post_id: i64, viewer_id: i64) -> Result<Vec<Comment>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.post_id, c.user_id, c.content, c.created_at, u.username, c.hidden_at
+             FROM comments c
+             JOIN users u ON c.user_id = u.id
+             WHERE c.post_id = ?1
+               AND c.hidden_at IS NULL
+               AND c.user_id NOT IN (SELECT target_user_id FROM user_blocks WHERE user_id = ?2)
+             ORDER BY c.created_at ASC, c.id ASC"
+        )?;
+
+        let rows = stmt.query_map(params![post_id, viewer_id], Self::comment_from_row)?;
+
+        let mut result = Vec::new();
+        for comment in rows {
+            result.push(comment?);
+        }
+        Ok(result)
+    }
+
+    /// One comment, hidden or not, for moderators reviewing a report
+    pub fn get_comment(&self, comment_id: i64) -> Result<Option<Comment>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let comment = conn
+            .query_row(
+                "SELECT c.id, c.post_id, c.user_id, c.content, c.created_at, u.username, c.hidden_at
+                 FROM comments c
+                 JOIN users u ON c.user_id = u.id
+                 WHERE c.id = ?1",
+                params![comment_id],
+                Self::comment_from_row,
+            )
+            .optional()?;
+        Ok(comment)
+    }
+
+    /// Everything a user has commented, oldest first
+    pub fn get_user_comments(&self, user_id: i64) -> Result<Vec<Comment>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.post_id, c.user_id, c.content, c.created_at, u.username, c.hidden_at
+             FROM comments c
+             JOIN users u ON c.user_id = u.id
+             WHERE c.user_id = ?1
+             ORDER BY c.created_at ASC, c.id ASC"
+        )?;
+
+        let rows = stmt.query_map(params![user_id], Self::comment_from_row)?;
+
+        let mut result = Vec::new();
+        for comment in rows {
+            result.push(comment?);
+        }
+        Ok(result)
+    }
+
+    pub fn is_subscribed(&self, user_id: i64, post_id: i64) -> Result<bool> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let subscribed = conn.query_row(
+            &format!("SELECT EXISTS (SELECT 1 FROM ({}) WHERE user_id = ?2)", SUBSCRIBERS),
+            params![post_id, user_id],
+            |row| row.get(0),
+        )?;
+        Ok(subscribed)
+    }
+
+    pub fn set_subscribed(&self, user_id: i64, post_id: i64, subscribed: bool) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+        Self::write_subscription(&conn, user_id, post_id, subscribed)
+    }
+
+    fn write_subscription(conn: &Connection, user_id: i64, post_id: i64, subscribed: bool) -> Result<()> {
+        conn.execute(
+            "INSERT INTO comment_subscriptions (user_id, post_id, subscribed, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(user_id, post_id) DO UPDATE
+             SET subscribed = excluded.subscribed, updated_at = excluded.updated_at",
+            params![user_id, post_id, subscribed, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+}
@@ -1,10 +1,143 @@
 // src/database.rs
 
-use rusqlite::{Connection, Result};
+use rusqlite::{params_from_iter, Connection, Result, Row, Transaction, TransactionBehavior};
+use rusqlite::types::Value;
 use std::sync::{Arc, Mutex};
-use crate::models::{User, Post};
+use crate::models::{Cursor, Page, PageRequest, User, Post, PostKind, Theme};
+use crate::ranking::RankingFormula;
 use chrono::{DateTime, Utc};
 
+/// Column list shared by every post query; pair with `Database::post_from_row`.
+pub const POST_SELECT: &str =
+    "SELECT p.id, p.user_id, p.title, p.content, p.created_at, p.updated_at, u.username,
+            p.kind, p.original_post_id, p.hidden_at, p.group_id, g.slug,
+            u.display_name, u.pronouns
+     FROM posts p
+     JOIN users u ON p.user_id = u.id
+     LEFT JOIN blog_groups g ON p.group_id = g.id";
+
+/// Column list shared by every user query; pair with `Database::user_from_row`.
+pub const USER_SELECT: &str =
+    "SELECT id, username, ssh_key, bio, created_at, is_admin, suspended_at, last_seen_at,
+            display_name, links, pronouns, timezone, avatar, login_name, theme
+     FROM users";
+
+/// Appended to post queries whose viewer is bound as `?1`: drops posts hidden
+/// by moderators and posts by users the viewer muted or blocked, including
+/// reshares of their posts.
+pub const VISIBLE_POSTS_FILTER: &str =
+    "p.hidden_at IS NULL
+     AND p.user_id NOT IN (SELECT target_user_id FROM user_blocks WHERE user_id = ?1)
+     AND NOT EXISTS (
+         SELECT 1 FROM posts o
+         WHERE o.id = p.original_post_id
+           AND o.user_id IN (SELECT target_user_id FROM user_blocks WHERE user_id = ?1)
+     )";
+
+/// Direction of a paged post listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostOrder {
+    NewestFirst,
+    OldestFirst,
+}
+
+/// Schema changes applied on top of `init_tables`, in order. The index of the
+/// last applied entry is stored in `PRAGMA user_version`, so each runs once.
+const MIGRATIONS: &[&str] = &[
+    // Reblogs and quote posts
+    "ALTER TABLE posts ADD COLUMN kind TEXT NOT NULL DEFAULT 'post';
+     ALTER TABLE posts ADD COLUMN original_post_id INTEGER
+         REFERENCES posts(id) ON DELETE SET NULL;",
+    // Moderation: admins, suspensions and hidden posts
+    "ALTER TABLE users ADD COLUMN is_admin INTEGER NOT NULL DEFAULT 0;
+     ALTER TABLE users ADD COLUMN suspended_at TEXT;
+     ALTER TABLE posts ADD COLUMN hidden_at TEXT;",
+    // Unread tracking: session greeting and the "mark all read" watermark
+    "ALTER TABLE users ADD COLUMN last_seen_at TEXT;
+     ALTER TABLE users ADD COLUMN read_all_at TEXT;",
+    // Group blogs: a post may be published to a group instead of the author's blog
+    "ALTER TABLE posts ADD COLUMN group_id INTEGER REFERENCES blog_groups(id) ON DELETE SET NULL;",
+    // Editable profile fields; links are stored one per line
+    "ALTER TABLE users ADD COLUMN display_name TEXT;
+     ALTER TABLE users ADD COLUMN links TEXT;
+     ALTER TABLE users ADD COLUMN pronouns TEXT;
+     ALTER TABLE users ADD COLUMN timezone TEXT;
+     ALTER TABLE users ADD COLUMN avatar TEXT;",
+    // The system account used to log in no longer follows username changes
+    "ALTER TABLE users ADD COLUMN login_name TEXT;
+     UPDATE users SET login_name = username;
+     CREATE UNIQUE INDEX IF NOT EXISTS idx_users_login_name ON users(login_name);",
+    // Moderation history outlives deleted admin accounts, losing only the name
+    "CREATE TABLE moderation_actions_new (
+         id              INTEGER PRIMARY KEY AUTOINCREMENT,
+         admin_id        INTEGER,
+         report_id       INTEGER,
+         action          TEXT NOT NULL,
+         target_type     TEXT NOT NULL,
+         target_id       INTEGER NOT NULL,
+         note            TEXT,
+         created_at      TEXT NOT NULL,
+         FOREIGN KEY(admin_id) REFERENCES users(id) ON DELETE SET NULL,
+         FOREIGN KEY(report_id) REFERENCES reports(id) ON DELETE SET NULL
+     );
+     INSERT INTO moderation_actions_new SELECT * FROM moderation_actions;
+     DROP TABLE moderation_actions;
+     ALTER TABLE moderation_actions_new RENAME TO moderation_actions;
+     CREATE TABLE user_warnings_new (
+         id              INTEGER PRIMARY KEY AUTOINCREMENT,
+         user_id         INTEGER NOT NULL,
+         admin_id        INTEGER,
+         message         TEXT NOT NULL,
+         created_at      TEXT NOT NULL,
+         seen_at         TEXT,
+         FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+         FOREIGN KEY(admin_id) REFERENCES users(id) ON DELETE SET NULL
+     );
+     INSERT INTO user_warnings_new SELECT * FROM user_warnings;
+     DROP TABLE user_warnings;
+     ALTER TABLE user_warnings_new RENAME TO user_warnings;",
+    // Full-text index over post titles and bodies, kept in step by triggers
+    "CREATE VIRTUAL TABLE posts_fts USING fts5(
+         title, content,
+         content = 'posts', content_rowid = 'id',
+         tokenize = 'unicode61 remove_diacritics 2'
+     );
+     CREATE TRIGGER posts_fts_insert AFTER INSERT ON posts BEGIN
+         INSERT INTO posts_fts(rowid, title, content) VALUES (new.id, new.title, new.content);
+     END;
+     CREATE TRIGGER posts_fts_delete AFTER DELETE ON posts BEGIN
+         INSERT INTO posts_fts(posts_fts, rowid, title, content)
+         VALUES ('delete', old.id, old.title, old.content);
+     END;
+     CREATE TRIGGER posts_fts_update AFTER UPDATE OF title, content ON posts BEGIN
+         INSERT INTO posts_fts(posts_fts, rowid, title, content)
+         VALUES ('delete', old.id, old.title, old.content);
+         INSERT INTO posts_fts(rowid, title, content) VALUES (new.id, new.title, new.content);
+     END;
+     INSERT INTO posts_fts(posts_fts) VALUES ('rebuild');",
+    // Interface theme chosen in the profile
+    "ALTER TABLE users ADD COLUMN theme TEXT NOT NULL DEFAULT 'default';",
+    // Comments can be reported, and hidden by moderators like posts
+    "ALTER TABLE comments ADD COLUMN hidden_at TEXT;
+     CREATE TABLE reports_new (
+         id              INTEGER PRIMARY KEY AUTOINCREMENT,
+         reporter_id     INTEGER NOT NULL,
+         target_type     TEXT NOT NULL CHECK(target_type IN ('post', 'user', 'comment')),
+         target_id       INTEGER NOT NULL,
+         reason          TEXT NOT NULL,
+         details         TEXT,
+         status          TEXT NOT NULL DEFAULT 'open',
+         created_at      TEXT NOT NULL,
+         FOREIGN KEY(reporter_id) REFERENCES users(id) ON DELETE CASCADE
+     );
+     INSERT INTO reports_new SELECT * FROM reports;
+     DROP TABLE reports;
+     ALTER TABLE reports_new RENAME TO reports;",
+    // Keyset pagination walks posts by (created_at, id), overall and per author
+    "CREATE INDEX IF NOT EXISTS idx_posts_created ON posts(created_at, id);
+     CREATE INDEX IF NOT EXISTS idx_posts_user_created ON posts(user_id, created_at, id);",
+];
+
 #[derive(Clone)]
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
@@ -13,6 +146,8 @@ pub struct Database {
 impl Database {
     pub fn new(db_path: &str) -> Result<Self> {
         let conn = Connection::open(db_path)?;
+        // SQLite leaves foreign keys off by default; cascades depend on them
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
         let db = Database {
             conn: Arc::new(Mutex::new(conn)),
         };
@@ -54,135 +189,478 @@ impl Database {
             [],
         )?;
 
-        Ok(())
-    }
+        // Bookmarks table: a per-user reading list, cleaned up with the post
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS bookmarks (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id         INTEGER NOT NULL,
+                post_id         INTEGER NOT NULL,
+                note            TEXT,
+                created_at      TEXT NOT NULL,
+                UNIQUE(user_id, post_id),
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
 
-    pub fn create_user(&self, user: &User) -> Result<i64> {
-        let conn = self.conn.lock().unwrap();
+        // Mutes and blocks; 'block' implies everything 'mute' does
         conn.execute(
-            "INSERT INTO users (username, ssh_key, bio, created_at) VALUES (?1, ?2, ?3, ?4)",
-            (
-                &user.username,
-                &user.ssh_key,
-                &user.bio,
-                user.created_at.to_rfc3339(),
-            ),
+            "CREATE TABLE IF NOT EXISTS user_blocks (
+                user_id         INTEGER NOT NULL,
+                target_user_id  INTEGER NOT NULL,
+                kind            TEXT NOT NULL CHECK(kind IN ('mute', 'block')),
+                created_at      TEXT NOT NULL,
+                PRIMARY KEY(user_id, target_user_id),
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY(target_user_id) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
         )?;
-        Ok(conn.last_insert_rowid())
-    }
 
-    pub fn get_user_by_username(&self, username: &str) -> Result<Option<User>> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, username, ssh_key, bio, created_at 
-             FROM users 
-             WHERE username = ?1"
+        // Reports filed by readers against posts or users
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS reports (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                reporter_id     INTEGER NOT NULL,
+                target_type     TEXT NOT NULL CHECK(target_type IN ('post', 'user')),
+                target_id       INTEGER NOT NULL,
+                reason          TEXT NOT NULL,
+                details         TEXT,
+                status          TEXT NOT NULL DEFAULT 'open',
+                created_at      TEXT NOT NULL,
+                FOREIGN KEY(reporter_id) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
         )?;
-        
-        let mut rows = stmt.query([username])?;
-        if let Some(row) = rows.next()? {
-            let u = User {
-                id: Some(row.get(0)?),
-                username: row.get(1)?,
-                ssh_key: row.get(2)?,
-                bio: row.get(3)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-            };
-            Ok(Some(u))
-        } else {
-            Ok(None)
-        }
-    }
 
-    pub fn create_post(&self, post: &Post) -> Result<i64> {
-        let conn = self.conn.lock().unwrap();
+        // Audit log of every moderation decision
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS moderation_actions (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                admin_id        INTEGER NOT NULL,
+                report_id       INTEGER,
+                action          TEXT NOT NULL,
+                target_type     TEXT NOT NULL,
+                target_id       INTEGER NOT NULL,
+                note            TEXT,
+                created_at      TEXT NOT NULL,
+                FOREIGN KEY(admin_id) REFERENCES users(id),
+                FOREIGN KEY(report_id) REFERENCES reports(id) ON DELETE SET NULL
+            )",
+            [],
+        )?;
+
+        // Warnings shown to a user the next time they log in
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_warnings (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id         INTEGER NOT NULL,
+                admin_id        INTEGER NOT NULL,
+                message         TEXT NOT NULL,
+                created_at      TEXT NOT NULL,
+                seen_at         TEXT,
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY(admin_id) REFERENCES users(id)
+            )",
+            [],
+        )?;
+
+        // Per-post read markers; anything older than users.read_all_at counts as read
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS post_reads (
+                user_id         INTEGER NOT NULL,
+                post_id         INTEGER NOT NULL,
+                read_at         TEXT NOT NULL,
+                PRIMARY KEY(user_id, post_id),
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Group blogs ("groups" is an SQL keyword), their members and pending invites
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS blog_groups (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                slug            TEXT UNIQUE NOT NULL,
+                name            TEXT NOT NULL,
+                description     TEXT,
+                created_at      TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS group_members (
+                group_id        INTEGER NOT NULL,
+                user_id         INTEGER NOT NULL,
+                role            TEXT NOT NULL CHECK(role IN ('owner', 'editor', 'member')),
+                joined_at       TEXT NOT NULL,
+                PRIMARY KEY(group_id, user_id),
+                FOREIGN KEY(group_id) REFERENCES blog_groups(id) ON DELETE CASCADE,
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS group_invites (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                group_id        INTEGER NOT NULL,
+                user_id         INTEGER NOT NULL,
+                invited_by      INTEGER NOT NULL,
+                created_at      TEXT NOT NULL,
+                UNIQUE(group_id, user_id),
+                FOREIGN KEY(group_id) REFERENCES blog_groups(id) ON DELETE CASCADE,
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY(invited_by) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Former usernames, so old mentions and links still find the account
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS username_aliases (
+                alias           TEXT PRIMARY KEY,
+                user_id         INTEGER NOT NULL,
+                created_at      TEXT NOT NULL,
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Comments on posts
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS comments (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                post_id         INTEGER NOT NULL,
+                user_id         INTEGER NOT NULL,
+                content         TEXT NOT NULL,
+                created_at      TEXT NOT NULL,
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE,
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Comment thread subscriptions. Authors follow their own posts unless a
+        // row says otherwise, so only explicit choices are stored.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS comment_subscriptions (
+                user_id         INTEGER NOT NULL,
+                post_id         INTEGER NOT NULL,
+                subscribed      INTEGER NOT NULL,
+                updated_at      TEXT NOT NULL,
+                PRIMARY KEY(user_id, post_id),
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Per-user notifications
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notifications (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id         INTEGER NOT NULL,
+                kind            TEXT NOT NULL,
+                post_id         INTEGER,
+                actor_id        INTEGER,
+                message         TEXT NOT NULL,
+                created_at      TEXT NOT NULL,
+                read_at         TEXT,
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE,
+                FOREIGN KEY(actor_id) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Named searches; those with alerts on are matched against each new post
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS saved_searches (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id         INTEGER NOT NULL,
+                name            TEXT NOT NULL,
+                query           TEXT NOT NULL,
+                alerts          INTEGER NOT NULL DEFAULT 0,
+                created_at      TEXT NOT NULL,
+                UNIQUE(user_id, name),
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Polls attached to posts, their options and one row per chosen option
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS polls (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                post_id         INTEGER NOT NULL UNIQUE,
+                question        TEXT NOT NULL,
+                multiple_choice INTEGER NOT NULL DEFAULT 0,
+                closes_at       TEXT,
+                created_at      TEXT NOT NULL,
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS poll_options (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                poll_id         INTEGER NOT NULL,
+                position        INTEGER NOT NULL,
+                label           TEXT NOT NULL,
+                FOREIGN KEY(poll_id) REFERENCES polls(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS poll_votes (
+                poll_id         INTEGER NOT NULL,
+                option_id       INTEGER NOT NULL,
+                user_id         INTEGER NOT NULL,
+                created_at      TEXT NOT NULL,
+                PRIMARY KEY(option_id, user_id),
+                FOREIGN KEY(poll_id) REFERENCES polls(id) ON DELETE CASCADE,
+                FOREIGN KEY(option_id) REFERENCES poll_options(id) ON DELETE CASCADE,
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // One row per reader who opened a post, for popularity ranking
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS post_views (
+                post_id         INTEGER NOT NULL,
+                user_id         INTEGER NOT NULL,
+                viewed_at       TEXT NOT NULL,
+                PRIMARY KEY(post_id, user_id),
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE,
+                FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Popularity scores, recomputed periodically by RankingManager
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS post_scores (
+                post_id         INTEGER PRIMARY KEY,
+                trending        REAL NOT NULL DEFAULT 0,
+                all_time        REAL NOT NULL DEFAULT 0,
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // The admin-tunable ranking formula; a single row
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ranking_formula (
+                id              INTEGER PRIMARY KEY CHECK(id = 1),
+                view_weight     REAL NOT NULL,
+                comment_weight  REAL NOT NULL,
+                bookmark_weight REAL NOT NULL,
+                reshare_weight  REAL NOT NULL,
+                half_life_hours REAL NOT NULL,
+                refreshed_at    TEXT
+            )",
+            [],
+        )?;
+        let formula = RankingFormula::default();
         conn.execute(
-            "INSERT INTO posts (user_id, title, content, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT OR IGNORE INTO ranking_formula
+                 (id, view_weight, comment_weight, bookmark_weight, reshare_weight, half_life_hours)
+             VALUES (1, ?1, ?2, ?3, ?4, ?5)",
             (
-                post.user_id,
-                &post.title,
-                &post.content,
-                post.created_at.to_rfc3339(),
-                post.updated_at.to_rfc3339(),
+                formula.view_weight,
+                formula.comment_weight,
+                formula.bookmark_weight,
+                formula.reshare_weight,
+                formula.half_life_hours,
             ),
         )?;
-        Ok(conn.last_insert_rowid())
-    }
 
-    pub fn get_posts_by_user(&self, user_id: i64) -> Result<Vec<Post>> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT 
-                p.id, 
-                p.user_id, 
-                p.title, 
-                p.content, 
-                p.created_at, 
-                p.updated_at, 
-                u.username 
-             FROM posts p 
-             JOIN users u ON p.user_id = u.id 
-             WHERE p.user_id = ?1 
-             ORDER BY p.created_at DESC"
+        // Term index for related posts: normalised term frequencies per post,
+        // and how many posts contain each term (maintained by the triggers)
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS post_terms (
+                term            TEXT NOT NULL,
+                post_id         INTEGER NOT NULL,
+                weight          REAL NOT NULL,
+                PRIMARY KEY(term, post_id),
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE
+            ) WITHOUT ROWID;
+            CREATE INDEX IF NOT EXISTS idx_post_terms_post ON post_terms(post_id);
+
+            CREATE TABLE IF NOT EXISTS term_stats (
+                term            TEXT PRIMARY KEY,
+                posts           INTEGER NOT NULL
+            ) WITHOUT ROWID;
+
+            CREATE TABLE IF NOT EXISTS post_term_index (
+                post_id         INTEGER PRIMARY KEY,
+                indexed_at      TEXT NOT NULL,
+                FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE
+            );
+
+            CREATE TRIGGER IF NOT EXISTS post_terms_ai AFTER INSERT ON post_terms BEGIN
+                INSERT INTO term_stats (term, posts) VALUES (new.term, 1)
+                    ON CONFLICT(term) DO UPDATE SET posts = posts + 1;
+            END;
+            CREATE TRIGGER IF NOT EXISTS post_terms_ad AFTER DELETE ON post_terms BEGIN
+                UPDATE term_stats SET posts = posts - 1 WHERE term = old.term;
+                DELETE FROM term_stats WHERE term = old.term AND posts <= 0;
+            END;",
         )?;
-        
-        let mut rows = stmt.query([user_id])?;
-        let mut posts = Vec::new();
-        while let Some(row) = rows.next()? {
-            let post = Post {
-                id: Some(row.get(0)?),
-                user_id: row.get(1)?,
-                title: row.get(2)?,
-                content: row.get(3)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-                author_username: Some(row.get(6)?),
-            };
-            posts.push(post);
+
+        Self::run_migrations(&conn)
+    }
+
+    fn run_migrations(conn: &Connection) -> Result<()> {
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version >= MIGRATIONS.len() {
+            return Ok(());
         }
-        Ok(posts)
+
+        // Rebuilding a table drops the old one, which with foreign keys on
+        // would cascade to every row that refers to it. SQLite ignores this
+        // pragma inside a transaction, so it is switched off around them all.
+        conn.execute_batch("PRAGMA foreign_keys = OFF;")?;
+        let result = Self::apply_migrations(conn, version);
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        result
     }
 
-    pub fn get_all_posts(&self) -> Result<Vec<Post>> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT 
-                p.id, 
-                p.user_id, 
-                p.title, 
-                p.content, 
-                p.created_at, 
-                p.updated_at, 
-                u.username 
-             FROM posts p 
-             JOIN users u ON p.user_id = u.id 
-             ORDER BY p.created_at DESC"
-        )?;
-        
-        let mut rows = stmt.query([])?;
-        let mut posts = Vec::new();
-        while let Some(row) = rows.next()? {
-            let post = Post {
-                id: Some(row.get(0)?),
-                user_id: row.get(1)?,
-                title: row.get(2)?,
-                content: row.get(3)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-                author_username: Some(row.get(6)?),
-            };
-            posts.push(post);
+    fn apply_migrations(conn: &Connection, version: usize) -> Result<()> {
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            // Each login is its own process, so another may be upgrading too.
+            // Take the write lock first, then check the version again: the
+            // migration and its version bump commit together or not at all.
+            let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+            let current: usize = tx.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+            if current == index {
+                tx.execute_batch(migration)?;
+                tx.pragma_update(None, "user_version", index + 1)?;
+            }
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Build a `User` from a row selected with `USER_SELECT`
+    pub fn user_from_row(row: &Row) -> Result<User> {
+        Ok(User {
+            id: Some(row.get(0)?),
+            username: row.get(1)?,
+            ssh_key: row.get(2)?,
+            bio: row.get(3)?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            is_admin: row.get(5)?,
+            suspended_at: row
+                .get::<_, Option<String>>(6)?
+                .map(|s| DateTime::parse_from_rfc3339(&s).unwrap().with_timezone(&Utc)),
+            last_seen_at: row
+                .get::<_, Option<String>>(7)?
+                .map(|s| DateTime::parse_from_rfc3339(&s).unwrap().with_timezone(&Utc)),
+            display_name: row.get(8)?,
+            links: row
+                .get::<_, Option<String>>(9)?
+                .map(|s| s.lines().map(str::to_string).collect())
+                .unwrap_or_default(),
+            pronouns: row.get(10)?,
+            timezone: row.get(11)?,
+            avatar: row.get(12)?,
+            login_name: row.get(13)?,
+            theme: Theme::parse(&row.get::<_, String>(14)?),
+        })
+    }
+
+    /// One page of `POST_SELECT` rows matching `filter`, whose placeholders
+    /// are bound from `params` starting at `?1`. Pages are cut with a keyset
+    /// on `(created_at, id)`, so no page costs more than `limit + 1` rows.
+    pub fn query_post_page(
+        conn: &Connection,
+        filter: &str,
+        mut params: Vec<Value>,
+        order: PostOrder,
+        request: PageRequest,
+        limit: usize,
+    ) -> Result<Page<Post>> {
+        // Walking backwards means flipping the order, then the rows
+        let backwards = matches!(request, PageRequest::Before(_));
+        let descending = (order == PostOrder::NewestFirst) != backwards;
+        let (comparison, direction) = if descending { ("<", "DESC") } else { (">", "ASC") };
+
+        let cursor_filter = match request {
+            PageRequest::First => String::new(),
+            PageRequest::After(cursor) | PageRequest::Before(cursor) => {
+                params.push(Value::Text(cursor.created_at.to_rfc3339()));
+                params.push(Value::Integer(cursor.id));
+                format!(
+                    " AND (p.created_at, p.id) {} (?{}, ?{})",
+                    comparison,
+                    params.len() - 1,
+                    params.len()
+                )
+            }
+        };
+        params.push(Value::Integer(limit as i64 + 1));
+
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE ({}){} ORDER BY p.created_at {dir}, p.id {dir} LIMIT ?{}",
+            POST_SELECT,
+            filter,
+            cursor_filter,
+            params.len(),
+            dir = direction
+        ))?;
+        let mut items = stmt
+            .query_map(params_from_iter(params), Self::post_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        let has_more = items.len() > limit;
+        items.truncate(limit);
+        if backwards {
+            items.reverse();
         }
-        Ok(posts)
+
+        let first = items.first().map(Cursor::of);
+        let last = items.last().map(Cursor::of);
+        let (next, prev) = match request {
+            PageRequest::First => (if has_more { last } else { None }, None),
+            PageRequest::After(_) => (if has_more { last } else { None }, first),
+            PageRequest::Before(_) => (last, if has_more { first } else { None }),
+        };
+        Ok(Page { items, next, prev })
+    }
+
+    /// Build a `Post` from a row selected with `POST_SELECT`
+    pub fn post_from_row(row: &Row) -> Result<Post> {
+        Ok(Post {
+            id: Some(row.get(0)?),
+            user_id: row.get(1)?,
+            title: row.get(2)?,
+            content: row.get(3)?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            author_username: Some(row.get(6)?),
+            kind: PostKind::parse(&row.get::<_, String>(7)?),
+            original_post_id: row.get(8)?,
+            hidden_at: row
+                .get::<_, Option<String>>(9)?
+                .map(|s| DateTime::parse_from_rfc3339(&s).unwrap().with_timezone(&Utc)),
+            group_id: row.get(10)?,
+            group_slug: row.get(11)?,
+            author_display_name: row.get(12)?,
+            author_pronouns: row.get(13)?,
+        })
     }
 }
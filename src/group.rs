@@ -0,0 +1,291 @@
+// src/group.rs
+
+use crate::models::{Group, GroupInvite, GroupMember, GroupRole};
+use crate::database::Database;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+
+const GROUP_SELECT: &str =
+    "SELECT g.id, g.slug, g.name, g.description, g.created_at,
+            (SELECT COUNT(*) FROM group_members m WHERE m.group_id = g.id)
+     FROM blog_groups g";
+
+pub struct GroupManager {
+    db: Database,
+}
+
+impl GroupManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    fn group_from_row(row: &Row) -> rusqlite::Result<Group> {
+        Ok(Group {
+            id: Some(row.get(0)?),
+            slug: row.get(1)?,
+            name: row.get(2)?,
+            description: row.get(3)?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            member_count: row.get(5)?,
+        })
+    }
+
+    fn role_of(conn: &Connection, group_id: i64, user_id: i64) -> Result<Option<GroupRole>> {
+        let role: Option<String> = conn
+            .query_row(
+                "SELECT role FROM group_members WHERE group_id = ?1 AND user_id = ?2",
+                params![group_id, user_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(role.and_then(|r| GroupRole::parse(&r)))
+    }
+
+    /// Group slugs are what people type after '#': lowercase letters, digits and '-'
+    pub fn validate_slug(slug: &str) -> Result<()> {
+        if slug.len() < 3 {
+            return Err(Error::msg("Group names must be at least 3 characters long"));
+        }
+        if !slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+            return Err(Error::msg(
+                "Group names can only contain lowercase letters, numbers and hyphens",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Create a group with `owner_id` as its first owner
+    pub fn create_group(&self, group: &mut Group, owner_id: i64) -> Result<()> {
+        Self::validate_slug(&group.slug)?;
+
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO blog_groups (slug, name, description, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![group.slug, group.name, group.description, group.created_at.to_rfc3339()],
+        )?;
+        let group_id = tx.last_insert_rowid();
+        tx.execute(
+            "INSERT INTO group_members (group_id, user_id, role, joined_at) VALUES (?1, ?2, 'owner', ?3)",
+            params![group_id, owner_id, group.created_at.to_rfc3339()],
+        )?;
+
+        tx.commit()?;
+        group.id = Some(group_id);
+        group.member_count = 1;
+        Ok(())
+    }
+
+    pub fn get_group_by_slug(&self, slug: &str) -> Result<Option<Group>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!("{} WHERE g.slug = ?1", GROUP_SELECT))?;
+        let mut rows = stmt.query(params![slug.trim_start_matches('#')])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(Self::group_from_row(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Groups the user belongs to, with their role in each
+    pub fn get_user_groups(&self, user_id: i64) -> Result<Vec<(Group, GroupRole)>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT g.id, g.slug, g.name, g.description, g.created_at,
+                    (SELECT COUNT(*) FROM group_members m WHERE m.group_id = g.id),
+                    me.role
+             FROM blog_groups g
+             JOIN group_members me ON me.group_id = g.id AND me.user_id = ?1
+             ORDER BY g.slug"
+        )?;
+
+        let rows = stmt.query_map(params![user_id], |row| {
+            let role: String = row.get(6)?;
+            Ok((
+                Self::group_from_row(row)?,
+                GroupRole::parse(&role).unwrap_or(GroupRole::Member),
+            ))
+        })?;
+
+        let mut result = Vec::new();
+        for group in rows {
+            result.push(group?);
+        }
+        Ok(result)
+    }
+
+    pub fn get_role(&self, group_id: i64, user_id: i64) -> Result<Option<GroupRole>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+        Self::role_of(&conn, group_id, user_id)
+    }
+
+    /// Members ordered owners first, then editors, then everyone else
+    pub fn get_members(&self, group_id: i64) -> Result<Vec<GroupMember>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT m.user_id, u.username, m.role, m.joined_at
+             FROM group_members m
+             JOIN users u ON m.user_id = u.id
+             WHERE m.group_id = ?1
+             ORDER BY CASE m.role WHEN 'owner' THEN 0 WHEN 'editor' THEN 1 ELSE 2 END, u.username"
+        )?;
+
+        let rows = stmt.query_map(params![group_id], |row| {
+            Ok(GroupMember {
+                user_id: row.get(0)?,
+                username: row.get(1)?,
+                role: GroupRole::parse(&row.get::<_, String>(2)?).unwrap_or(GroupRole::Member),
+                joined_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for member in rows {
+            result.push(member?);
+        }
+        Ok(result)
+    }
+
+    /// Invite `invitee_id`; the inviter must be an editor or owner
+    pub fn invite(&self, group_id: i64, inviter_id: i64, invitee_id: i64) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        match Self::role_of(&conn, group_id, inviter_id)? {
+            Some(role) if role.can_invite() => {}
+            _ => return Err(Error::msg("Only editors and owners can invite people")),
+        }
+        if Self::role_of(&conn, group_id, invitee_id)?.is_some() {
+            return Err(Error::msg("That user is already a member"));
+        }
+
+        conn.execute(
+            "INSERT OR IGNORE INTO group_invites (group_id, user_id, invited_by, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![group_id, invitee_id, inviter_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_pending_invites(&self, user_id: i64) -> Result<Vec<GroupInvite>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT i.id, i.group_id, g.slug, u.username, i.created_at
+             FROM group_invites i
+             JOIN blog_groups g ON i.group_id = g.id
+             JOIN users u ON i.invited_by = u.id
+             WHERE i.user_id = ?1
+             ORDER BY i.created_at"
+        )?;
+
+        let rows = stmt.query_map(params![user_id], |row| {
+            Ok(GroupInvite {
+                id: row.get(0)?,
+                group_id: row.get(1)?,
+                group_slug: row.get(2)?,
+                invited_by: row.get(3)?,
+                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for invite in rows {
+            result.push(invite?);
+        }
+        Ok(result)
+    }
+
+    /// Accepting joins the group as a member; either way the invite is used up
+    pub fn respond_to_invite(&self, invite: &GroupInvite, user_id: i64, accept: bool) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let removed = tx.execute(
+            "DELETE FROM group_invites WHERE id = ?1 AND user_id = ?2",
+            params![invite.id, user_id],
+        )?;
+        if removed == 0 {
+            return Err(Error::msg("That invite no longer exists"));
+        }
+        if accept {
+            tx.execute(
+                "INSERT OR IGNORE INTO group_members (group_id, user_id, role, joined_at)
+                 VALUES (?1, ?2, 'member', ?3)",
+                params![invite.group_id, user_id, Utc::now().to_rfc3339()],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Change a member's role; owners only, and a group always keeps an owner
+    pub fn set_role(&self, group_id: i64, actor_id: i64, target_id: i64, role: GroupRole) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        match Self::role_of(&conn, group_id, actor_id)? {
+            Some(actor_role) if actor_role.can_manage() => {}
+            _ => return Err(Error::msg("Only owners can change roles")),
+        }
+        match Self::role_of(&conn, group_id, target_id)? {
+            None => return Err(Error::msg("That user is not a member")),
+            Some(GroupRole::Owner) if role != GroupRole::Owner => {
+                Self::ensure_other_owner(&conn, group_id, target_id)?
+            }
+            Some(_) => {}
+        }
+
+        conn.execute(
+            "UPDATE group_members SET role = ?1 WHERE group_id = ?2 AND user_id = ?3",
+            params![role.as_str(), group_id, target_id],
+        )?;
+        Ok(())
+    }
+
+    /// Leave a group; the last owner has to hand over first
+    pub fn leave(&self, group_id: i64, user_id: i64) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        if Self::role_of(&conn, group_id, user_id)? == Some(GroupRole::Owner) {
+            Self::ensure_other_owner(&conn, group_id, user_id)?;
+        }
+        conn.execute(
+            "DELETE FROM group_members WHERE group_id = ?1 AND user_id = ?2",
+            params![group_id, user_id],
+        )?;
+        Ok(())
+    }
+
+    fn ensure_other_owner(conn: &Connection, group_id: i64, user_id: i64) -> Result<()> {
+        let owners: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM group_members WHERE group_id = ?1 AND role = 'owner' AND user_id != ?2",
+            params![group_id, user_id],
+            |row| row.get(0),
+        )?;
+        if owners == 0 {
+            return Err(Error::msg("A group needs at least one other owner first"));
+        }
+        Ok(())
+    }
+}
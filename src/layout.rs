@@ -0,0 +1,202 @@
+// src/layout.rs
+//
+// Fitting line-mode output to the terminal: the width to lay out for, rules
+// that span it, and word wrapping that measures text the way the terminal
+// draws it, so wide characters and most emoji count as two columns.
+
+use crate::theme::{self, Icon};
+use std::env;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Width assumed when there is no terminal to ask
+const DEFAULT_WIDTH: usize = 80;
+/// Lines longer than this are hard to read, however wide the window
+const MAX_WIDTH: usize = 100;
+/// Narrowest layout attempted, about the width of a phone held upright
+const MIN_WIDTH: usize = 20;
+
+static WIDTH: AtomicUsize = AtomicUsize::new(DEFAULT_WIDTH);
+/// Set by SIGWINCH until `take_resize` sees it
+static RESIZED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+fn resized() -> &'static AtomicBool {
+    RESIZED.get_or_init(|| {
+        let flag = Arc::new(AtomicBool::new(false));
+        // Should the handler fail to install, the first measurement stands
+        let _ = signal_hook::flag::register(signal_hook::consts::SIGWINCH, Arc::clone(&flag));
+        flag
+    })
+}
+
+/// Columns to lay out for, kept between MIN_WIDTH and MAX_WIDTH
+pub fn width() -> usize {
+    WIDTH.load(Ordering::Relaxed)
+}
+
+/// Lay out for a window `columns` wide. Without one, $COLUMNS is used, or
+/// DEFAULT_WIDTH.
+pub fn set_columns(columns: Option<usize>) {
+    resized().store(false, Ordering::Relaxed);
+    let width = columns
+        .or_else(|| env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok()))
+        .filter(|columns| *columns > 0)
+        .unwrap_or(DEFAULT_WIDTH)
+        .clamp(MIN_WIDTH, MAX_WIDTH);
+    WIDTH.store(width, Ordering::Relaxed);
+}
+
+/// True once after each SIGWINCH, when the window should be measured again
+pub fn take_resize() -> bool {
+    resized().swap(false, Ordering::Relaxed)
+}
+
+/// Columns `text` takes up on screen; colour and other escape sequences
+/// take none
+pub fn display_width(text: &str) -> usize {
+    if !text.contains('\x1b') {
+        return text.width();
+    }
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI: ESC '[' parameters, ended by a byte from '@' to '~'
+            if chars.next() == Some('[') {
+                chars.by_ref().find(|c| ('@'..='~').contains(c));
+            }
+            continue;
+        }
+        plain.push(c);
+    }
+    plain.width()
+}
+
+/// A horizontal rule across the layout width, in ASCII if the theme asks
+pub fn rule(ch: char) -> String {
+    theme::rule_char(ch).to_string().repeat(width())
+}
+
+/// `text` word-wrapped to the layout width
+pub fn fill(text: &str) -> String {
+    wrap(text, width()).join("\n")
+}
+
+/// `text` word-wrapped to fit after `prefix`, with `prefix` on every line
+pub fn indent(prefix: &str, text: &str) -> String {
+    let available = width().saturating_sub(display_width(prefix)).max(MIN_WIDTH / 2);
+    wrap(text, available)
+        .iter()
+        .map(|line| format!("{}{}", prefix, line).trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `text` padded with spaces to `width` columns
+pub fn pad(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(display_width(text))))
+}
+
+/// `text` cut to at most `width` columns, ending in an ellipsis if anything
+/// was cut
+pub fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let ellipsis = Icon::Ellipsis.to_string();
+    let mut result = String::new();
+    let mut used = display_width(&ellipsis);
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = display_width(grapheme);
+        if used + grapheme_width > width {
+            break;
+        }
+        result.push_str(grapheme);
+        used += grapheme_width;
+    }
+    result.push_str(&ellipsis);
+    result
+}
+
+/// Word-wrap `text` to `width` columns. Lines break at spaces; a word too
+/// long for a line of its own is split between characters. Continuation
+/// lines of list items and quotes line up under their text. Fenced code
+/// blocks and indented code are left exactly as written.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+
+    for line in text.lines() {
+        let fence = line.trim_start().starts_with("```");
+        if fence || in_fence || line.starts_with("    ") || line.starts_with('\t') {
+            if fence {
+                in_fence = !in_fence;
+            }
+            lines.push(line.to_string());
+            continue;
+        }
+        wrap_line(line.trim_end(), width, &mut lines);
+    }
+    lines
+}
+
+fn wrap_line(line: &str, width: usize, lines: &mut Vec<String>) {
+    let words = line.trim_start();
+    if words.is_empty() {
+        lines.push(String::new());
+        return;
+    }
+    let mut current = line[..line.len() - words.len()].to_string();
+    let mut used = display_width(&current);
+    let mut start = used; // Columns taken by indentation on the current line
+
+    let hang = " ".repeat(hanging_indent(line).min(width / 2));
+    for word in words.split(' ').filter(|word| !word.is_empty()) {
+        let word_width = display_width(word);
+        if used > start && used + 1 + word_width > width {
+            lines.push(std::mem::replace(&mut current, hang.clone()));
+            used = hang.len();
+            start = used;
+        }
+        if used > start {
+            current.push(' ');
+            used += 1;
+        }
+
+        if used + word_width <= width {
+            current.push_str(word);
+            used += word_width;
+            continue;
+        }
+        // Longer than a whole line: break it wherever the line is full
+        for grapheme in word.graphemes(true) {
+            let grapheme_width = display_width(grapheme);
+            if used > start && used + grapheme_width > width {
+                lines.push(std::mem::replace(&mut current, hang.clone()));
+                used = hang.len();
+                start = used;
+            }
+            current.push_str(grapheme);
+            used += grapheme_width;
+        }
+    }
+    lines.push(current);
+}
+
+/// Columns of leading space plus any list or quote marker ("- ", "* ",
+/// "+ ", "> ", "1. ", "1) ")
+fn hanging_indent(line: &str) -> usize {
+    let text = line.trim_start();
+    let leading = line.len() - text.len();
+    if ["- ", "* ", "+ ", "> "].iter().any(|marker| text.starts_with(marker)) {
+        return leading + 2;
+    }
+    let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && (text[digits..].starts_with(". ") || text[digits..].starts_with(") ")) {
+        return leading + digits + 2;
+    }
+    leading
+}
@@ -0,0 +1,30 @@
+// src/lib.rs
+//
+// The blog as a library, so benchmarks can drive it; `main.rs` is the SSH
+// entry point.
+
+pub mod models;
+pub mod database;
+pub mod user;
+pub mod post;
+pub mod bookmark;
+pub mod block;
+pub mod moderation;
+pub mod activity;
+pub mod watcher;
+pub mod poll;
+pub mod group;
+pub mod comment;
+pub mod notification;
+pub mod account;
+pub mod query;
+pub mod search;
+pub mod saved_search;
+pub mod related;
+pub mod ranking;
+pub mod theme;
+pub mod layout;
+pub mod session;
+pub mod pager;
+pub mod tui;
+pub mod cli;
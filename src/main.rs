@@ -1,17 +1,29 @@
 // src/main.rs
 
 use std::env;
-use crate::models::{User, Post};
-use crate::database::Database;
-use crate::user::UserManager;
-use crate::post::PostManager;
-use crate::cli::CLI;
-
-mod models;
-mod database;
-mod user;
-mod post;
-mod cli;
+use std::io;
+use app::models::User;
+use app::database::Database;
+use app::user::UserManager;
+use app::post::PostManager;
+use app::bookmark::BookmarkManager;
+use app::block::BlockManager;
+use app::moderation::ModerationManager;
+use app::activity::ActivityManager;
+use app::watcher::Watcher;
+use app::poll::PollManager;
+use app::group::GroupManager;
+use app::comment::CommentManager;
+use app::notification::NotificationManager;
+use app::account::AccountManager;
+use app::search::SearchManager;
+use app::saved_search::SavedSearchManager;
+use app::ranking::RankingManager;
+use app::related::RelatedManager;
+use app::tui::{self, Tui};
+use app::theme::{self, Icon};
+use app::session::{self, Stdio};
+use app::cli::CLI;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -32,6 +44,42 @@ fn main() {
                 }
                 return;
             }
+            "--set-admin" | "--unset-admin" => {
+                if args.len() >= 3 {
+                    handle_set_admin(&args[2], args[1] == "--set-admin");
+                } else {
+                    eprintln!("Usage: ssh-blog {} <username>", args[1]);
+                    std::process::exit(1);
+                }
+                return;
+            }
+            "--unsuspend" => {
+                if args.len() >= 3 {
+                    handle_unsuspend(&args[2]);
+                } else {
+                    eprintln!("Usage: ssh-blog --unsuspend <username>");
+                    std::process::exit(1);
+                }
+                return;
+            }
+            "--export" => {
+                if args.len() >= 3 {
+                    handle_export(&args[2]);
+                } else {
+                    eprintln!("Usage: ssh-blog --export <username> > account.tar");
+                    std::process::exit(1);
+                }
+                return;
+            }
+            "--delete-user" => {
+                if args.len() >= 3 {
+                    handle_delete_user(&args[2]);
+                } else {
+                    eprintln!("Usage: ssh-blog --delete-user <username>");
+                    std::process::exit(1);
+                }
+                return;
+            }
             "--init-db" => {
                 handle_db_init();
                 return;
@@ -41,50 +89,109 @@ fn main() {
     }
 
     let db = Database::new("/var/lib/ssh-blog/blog.db").expect("Failed to initialize database");
+
+    // `ssh host export > me.tar` is non-interactive: the archive is the only output
+    if env::var("SSH_ORIGINAL_COMMAND").is_ok_and(|command| command.trim() == "export") {
+        handle_self_export(db);
+        return;
+    }
+
     let user_manager = UserManager::new(db.clone());
     let post_manager = PostManager::new(db.clone());
-    let mut cli = CLI::new(user_manager, post_manager);
-
-    println!("🚀 Welcome to SSH Blog Platform!");
-    println!("Your terminal-based blogging experience starts here.\n");
+    let bookmark_manager = BookmarkManager::new(db.clone());
+    let block_manager = BlockManager::new(db.clone());
+    let moderation_manager = ModerationManager::new(db.clone());
+    let activity_manager = ActivityManager::new(db.clone());
+    let poll_manager = PollManager::new(db.clone());
+    let group_manager = GroupManager::new(db.clone());
+    let comment_manager = CommentManager::new(db.clone());
+    let notification_manager = NotificationManager::new(db.clone());
+    let account_manager = AccountManager::new(db.clone());
+    let search_manager = SearchManager::new(db.clone());
+    let saved_search_manager = SavedSearchManager::new(db.clone());
+    let ranking_manager = RankingManager::new(db.clone());
+    let related_manager = RelatedManager::new(db.clone());
+    // Index anything written before the term index existed; new posts are
+    // indexed as they are written
+    if let Err(e) = RelatedManager::catch_up(&db.get_connection().lock().unwrap()) {
+        eprintln!("Failed to update the related posts index: {}", e);
+    }
+    let mut cli = CLI::new(
+        Stdio,
+        user_manager,
+        post_manager,
+        bookmark_manager,
+        block_manager,
+        moderation_manager,
+        activity_manager,
+        poll_manager,
+        group_manager,
+        comment_manager,
+        notification_manager,
+        account_manager,
+        search_manager,
+        saved_search_manager,
+        ranking_manager,
+        related_manager,
+    );
 
     // Authenticate user based on SSH connection
     let current_user = match cli.authenticate_user() {
         Ok(user) => user,
         Err(e) => {
-            eprintln!("❌ Authentication failed: {}", e);
-            eprintln!("💡 If this is your first time, contact admin to register your account");
+            eprintln!("{}", theme::error(format_args!("Authentication failed: {}", e)));
+            eprintln!("{}", theme::tip("If this is your first time, contact admin to register your account"));
             return;
         }
     };
 
+    // Everything from here on is drawn in the user's theme
+    theme::apply(current_user.theme);
+    println!("{} Welcome to SSH Blog Platform!", Icon::Welcome);
+    println!("Your terminal-based blogging experience starts here.\n");
     println!("Welcome back, {}!", current_user.username);
     if let Some(bio) = &current_user.bio {
         println!("Bio: {}", bio);
     }
+    if let Err(e) = cli.greet_returning_user(&current_user) {
+        eprintln!("{}", theme::error(format_args!("Session ended: {}", e)));
+        return;
+    }
+
+    match Watcher::start(db.clone(), current_user.id.unwrap()) {
+        Ok(watcher) => cli.attach_watcher(watcher),
+        Err(e) => eprintln!("{}", theme::warning(format_args!("Live updates unavailable: {}", e))),
+    }
 
-    cli.run_main_loop(current_user);
+    if tui::is_available(cli.session()) {
+        if let Err(e) = Tui::new(db, current_user).run(&mut cli) {
+            eprintln!("{}", theme::error(format_args!("The full-screen view failed: {}", e)));
+        }
+    } else if let Err(e) = cli.run_main_loop(current_user) {
+        eprintln!("{}", theme::error(format_args!("Session ended: {}", e)));
+    }
 }
 
 fn handle_registration() {
-    println!("🔐 SSH Blog Registration");
+    session::fit_output(&Stdio);
+    println!("{} SSH Blog Registration", Icon::Locked);
     println!("Setting up your account...\n");
 
     let db = Database::new("/var/lib/ssh-blog/blog.db").expect("Failed to initialize database");
     let user_manager = UserManager::new(db);
 
-    match user_manager.register_user_with_ssh() {
+    match user_manager.register_user_with_ssh(&mut Stdio) {
         Ok(user) => {
-            println!("\n✅ Registration successful!");
+            println!("\n{}", theme::success("Registration successful!"));
             println!("Username: {}", user.username);
             if let Some(bio) = &user.bio {
                 println!("Bio: {}", bio);
             }
-            println!("\n🔑 Your SSH public key has been registered.");
+            println!("\n{} Your SSH public key has been registered.", Icon::Key);
             println!("You can now connect using: ssh {}@yourserver -p 2222", user.username);
         }
         Err(e) => {
-            eprintln!("❌ Registration failed: {}", e);
+            eprintln!("{}", theme::error(format_args!("Registration failed: {}", e)));
             std::process::exit(1);
         }
     }
@@ -94,7 +201,7 @@ fn handle_user_registration(username: &str, ssh_key: &str, bio: Option<&String>)
     let db = Database::new("/var/lib/ssh-blog/blog.db").expect("Failed to initialize database");
     let user_manager = UserManager::new(db);
     
-    let bio_str = bio.map(|s| s.clone());
+    let bio_str = bio.cloned();
     let mut user = User::new(username.to_string(), ssh_key.to_string(), bio_str);
     
     match user_manager.create_user_direct(&mut user) {
@@ -109,6 +216,49 @@ fn handle_user_registration(username: &str, ssh_key: &str, bio: Option<&String>)
     }
 }
 
+/// Note for the moderation log on changes made from the server's command
+/// line, where no account is logged in
+fn command_line_note() -> String {
+    let operator = env::var("SUDO_USER").or_else(|_| env::var("USER")).unwrap_or_else(|_| "unknown".to_string());
+    format!("From the server command line as {}", operator)
+}
+
+fn handle_set_admin(username: &str, is_admin: bool) {
+    let db = Database::new("/var/lib/ssh-blog/blog.db").expect("Failed to initialize database");
+    let moderation_manager = ModerationManager::new(db);
+
+    match moderation_manager.set_admin(username, is_admin, None, Some(&command_line_note())) {
+        Ok(_) => {
+            if is_admin {
+                println!("{} is now an admin", username);
+            } else {
+                println!("{} is no longer an admin", username);
+            }
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Failed to update user: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_unsuspend(username: &str) {
+    let db = Database::new("/var/lib/ssh-blog/blog.db").expect("Failed to initialize database");
+    let moderation_manager = ModerationManager::new(db);
+
+    match moderation_manager.lift_suspension(username, None, Some(&command_line_note())) {
+        Ok(_) => {
+            println!("Suspension lifted for {}", username);
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Failed to update user: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn handle_db_init() {
     match Database::new("/var/lib/ssh-blog/blog.db") {
         Ok(_) => {
@@ -121,3 +271,69 @@ fn handle_db_init() {
         }
     }
 }
+
+fn handle_self_export(db: Database) {
+    let user = match UserManager::new(db.clone()).authenticate_from_ssh() {
+        Ok(user) => user,
+        Err(e) => {
+            eprintln!("{}", theme::error(format_args!("Authentication failed: {}", e)));
+            std::process::exit(1);
+        }
+    };
+    write_export(db, &user);
+}
+
+fn handle_export(username: &str) {
+    let db = Database::new("/var/lib/ssh-blog/blog.db").expect("Failed to initialize database");
+    let user = match UserManager::new(db.clone()).find_by_username(username) {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            eprintln!("User '{}' not found", username);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to look up user: {}", e);
+            std::process::exit(1);
+        }
+    };
+    write_export(db, &user);
+}
+
+fn write_export(db: Database, user: &User) {
+    // The Markdown files are drawn in the account's own theme
+    theme::apply(user.theme);
+    let account_manager = AccountManager::new(db);
+    let result = account_manager
+        .export(user)
+        .and_then(|export| account_manager.write_archive(&export, io::stdout().lock()));
+    if let Err(e) = result {
+        eprintln!("Failed to export account: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn handle_delete_user(username: &str) {
+    let db = Database::new("/var/lib/ssh-blog/blog.db").expect("Failed to initialize database");
+    let user = match UserManager::new(db.clone()).find_by_username(username) {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            eprintln!("User '{}' not found", username);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to look up user: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match AccountManager::new(db).delete_account(user.id.unwrap()) {
+        Ok(_) => {
+            println!("Account {} deleted; remove the system login {} with manage-user.sh", user.username, user.login_name);
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Failed to delete account: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
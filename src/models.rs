@@ -10,16 +10,136 @@ pub struct User {
     pub ssh_key: String,
     pub bio: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub is_admin: bool,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub last_seen_at: Option<DateTime<Utc>>, // Start of the previous session
+    pub display_name: Option<String>,
+    pub links: Vec<String>,
+    pub pronouns: Option<String>,
+    pub timezone: Option<String>, // IANA name, e.g. "Europe/Berlin"
+    pub avatar: Option<String>,   // Multi-line ASCII art, see `User::AVATAR_MAX_LINES`
+    pub login_name: String, // System account used over SSH; set at registration, kept on rename
+    pub theme: Theme,
 }
 
 impl User {
     pub fn new(username: String, ssh_key: String, bio: Option<String>) -> Self {
         Self {
             id: None,
+            login_name: username.clone(),
             username,
             ssh_key,
             bio,
             created_at: Utc::now(),
+            is_admin: false,
+            suspended_at: None,
+            last_seen_at: None,
+            display_name: None,
+            links: Vec::new(),
+            pronouns: None,
+            timezone: None,
+            avatar: None,
+            theme: Theme::Default,
+        }
+    }
+
+    pub const DISPLAY_NAME_MAX_CHARS: usize = 50;
+    pub const BIO_MAX_CHARS: usize = 500;
+    pub const PRONOUNS_MAX_CHARS: usize = 30;
+    pub const MAX_LINKS: usize = 5;
+    pub const LINK_MAX_CHARS: usize = 200;
+    pub const AVATAR_MAX_LINES: usize = 8;
+    pub const AVATAR_MAX_WIDTH: usize = 32;
+
+    /// "Display Name (@username)", or just the username when no display name is set
+    pub fn display_label(&self) -> String {
+        author_label(&self.username, self.display_name.as_deref())
+    }
+}
+
+pub fn author_label(username: &str, display_name: Option<&str>) -> String {
+    match display_name {
+        Some(name) => format!("{} (@{})", name, username),
+        None => username.to_string(),
+    }
+}
+
+/// How a user wants the interface drawn; `theme.rs` does the drawing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    Default,
+    HighContrast,
+    Monochrome,
+    Ascii,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Default, Theme::HighContrast, Theme::Monochrome, Theme::Ascii];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Theme::Default => "default",
+            Theme::HighContrast => "high-contrast",
+            Theme::Monochrome => "monochrome",
+            Theme::Ascii => "ascii",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "high-contrast" => Theme::HighContrast,
+            "monochrome" => Theme::Monochrome,
+            "ascii" => Theme::Ascii,
+            _ => Theme::Default,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Theme::Default => "colour and emoji",
+            Theme::HighContrast => "bold, bright colours and no dimmed text",
+            Theme::Monochrome => "emoji without colour",
+            Theme::Ascii => "plain text markers instead of emoji, no colour",
+        }
+    }
+}
+
+/// What happened between the previous session and this one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisitSummary {
+    pub last_seen_at: DateTime<Utc>,
+    pub new_posts: i64,
+    pub new_comments: i64,
+}
+
+/// A directory entry: the user plus how many visible posts they have
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSummary {
+    pub user: User,
+    pub post_count: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PostKind {
+    Post,
+    Reblog,
+    Quote,
+}
+
+impl PostKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostKind::Post => "post",
+            PostKind::Reblog => "reblog",
+            PostKind::Quote => "quote",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "reblog" => PostKind::Reblog,
+            "quote" => PostKind::Quote,
+            _ => PostKind::Post,
         }
     }
 }
@@ -33,6 +153,13 @@ pub struct Post {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub author_username: Option<String>, // Newly added field
+    pub kind: PostKind,
+    pub original_post_id: Option<i64>, // Set for reblogs and quotes; NULL once the original is gone
+    pub hidden_at: Option<DateTime<Utc>>, // Set when a moderator hides the post
+    pub group_id: Option<i64>, // Set when published to a group blog
+    pub group_slug: Option<String>,
+    pub author_display_name: Option<String>,
+    pub author_pronouns: Option<String>,
 }
 
 impl Post {
@@ -46,6 +173,439 @@ impl Post {
             created_at: now,
             updated_at: now,
             author_username: None, // Default to None
+            kind: PostKind::Post,
+            original_post_id: None,
+            hidden_at: None,
+            group_id: None,
+            group_slug: None,
+            author_display_name: None,
+            author_pronouns: None,
+        }
+    }
+
+    /// A reblog (no commentary) or quote post pointing at `original`
+    pub fn new_reshare(user_id: i64, original: &Post, commentary: Option<String>) -> Self {
+        let kind = if commentary.is_some() { PostKind::Quote } else { PostKind::Reblog };
+        let mut post = Post::new(user_id, original.title.clone(), commentary.unwrap_or_default());
+        post.kind = kind;
+        post.original_post_id = original.id;
+        post
+    }
+}
+
+/// A position in a post listing ordered by `(created_at, id)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i64,
+}
+
+impl Cursor {
+    pub fn of(post: &Post) -> Self {
+        Self { created_at: post.created_at, id: post.id.unwrap_or(0) }
+    }
+}
+
+/// Which page of a listing to fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageRequest {
+    First,
+    After(Cursor),  // The page following the one ending at this cursor
+    Before(Cursor), // The page preceding the one starting at this cursor
+}
+
+/// Number of posts published in one calendar month (UTC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveMonth {
+    pub year: i32,
+    pub month: u32,
+    pub count: i64,
+}
+
+impl ArchiveMonth {
+    pub fn month_name(&self) -> &'static str {
+        const NAMES: [&str; 12] = [
+            "January", "February", "March", "April", "May", "June",
+            "July", "August", "September", "October", "November", "December",
+        ];
+        NAMES.get(self.month as usize - 1).copied().unwrap_or("?")
+    }
+}
+
+/// One page of a listing; `next` and `prev` are set when there is more to see
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<Cursor>,
+    pub prev: Option<Cursor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: Option<i64>,
+    pub user_id: i64,
+    pub post_id: i64,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub post_title: Option<String>,
+    pub author_username: Option<String>,
+}
+
+impl Bookmark {
+    pub fn new(user_id: i64, post_id: i64, note: Option<String>) -> Self {
+        Self {
+            id: None,
+            user_id,
+            post_id,
+            note,
+            created_at: Utc::now(),
+            post_title: None,
+            author_username: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockKind {
+    Mute,
+    Block,
+}
+
+impl BlockKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockKind::Mute => "mute",
+            BlockKind::Block => "block",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "block" => BlockKind::Block,
+            _ => BlockKind::Mute,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserBlock {
+    pub user_id: i64,
+    pub target_user_id: i64,
+    pub kind: BlockKind,
+    pub created_at: DateTime<Utc>,
+    pub target_username: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportTarget {
+    Post,
+    User,
+    Comment,
+}
+
+impl ReportTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportTarget::Post => "post",
+            ReportTarget::User => "user",
+            ReportTarget::Comment => "comment",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "user" => ReportTarget::User,
+            "comment" => ReportTarget::Comment,
+            _ => ReportTarget::Post,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportReason {
+    Spam,
+    Harassment,
+    Inappropriate,
+    Other,
+}
+
+impl ReportReason {
+    pub const ALL: [ReportReason; 4] = [
+        ReportReason::Spam,
+        ReportReason::Harassment,
+        ReportReason::Inappropriate,
+        ReportReason::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportReason::Spam => "spam",
+            ReportReason::Harassment => "harassment",
+            ReportReason::Inappropriate => "inappropriate",
+            ReportReason::Other => "other",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "spam" => ReportReason::Spam,
+            "harassment" => ReportReason::Harassment,
+            "inappropriate" => ReportReason::Inappropriate,
+            _ => ReportReason::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub id: Option<i64>,
+    pub reporter_id: i64,
+    pub target: ReportTarget,
+    pub target_id: i64,
+    pub reason: ReportReason,
+    pub details: Option<String>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub reporter_username: Option<String>,
+}
+
+impl Report {
+    pub fn new(
+        reporter_id: i64,
+        target: ReportTarget,
+        target_id: i64,
+        reason: ReportReason,
+        details: Option<String>,
+    ) -> Self {
+        Self {
+            id: None,
+            reporter_id,
+            target,
+            target_id,
+            reason,
+            details,
+            status: "open".to_string(),
+            created_at: Utc::now(),
+            reporter_username: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModerationAction {
+    Dismiss,
+    Hide,
+    Warn,
+    Suspend,
+    // Taken on an account directly rather than on a report
+    Unsuspend,
+    GrantAdmin,
+    RevokeAdmin,
+}
+
+impl ModerationAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModerationAction::Dismiss => "dismiss",
+            ModerationAction::Hide => "hide",
+            ModerationAction::Warn => "warn",
+            ModerationAction::Suspend => "suspend",
+            ModerationAction::Unsuspend => "unsuspend",
+            ModerationAction::GrantAdmin => "grant-admin",
+            ModerationAction::RevokeAdmin => "revoke-admin",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationLogEntry {
+    pub id: i64,
+    pub admin_username: String,
+    pub report_id: Option<i64>,
+    pub action: String,
+    pub target: ReportTarget,
+    pub target_id: i64,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserWarning {
+    pub id: i64,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollOption {
+    pub id: Option<i64>,
+    pub label: String,
+    pub votes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poll {
+    pub id: Option<i64>,
+    pub post_id: i64,
+    pub question: String,
+    pub multiple_choice: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub options: Vec<PollOption>,
+}
+
+impl Poll {
+    pub const MIN_OPTIONS: usize = 2;
+    pub const MAX_OPTIONS: usize = 10;
+
+    pub fn new(
+        question: String,
+        labels: Vec<String>,
+        multiple_choice: bool,
+        closes_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id: None,
+            post_id: 0,
+            question,
+            multiple_choice,
+            closes_at,
+            created_at: Utc::now(),
+            options: labels
+                .into_iter()
+                .map(|label| PollOption { id: None, label, votes: 0 })
+                .collect(),
+        }
+    }
+
+    /// Results are frozen once the closing time has passed
+    pub fn is_closed(&self) -> bool {
+        self.closes_at.is_some_and(|closes_at| closes_at <= Utc::now())
+    }
+
+    pub fn total_votes(&self) -> i64 {
+        self.options.iter().map(|o| o.votes).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupRole {
+    Owner,
+    Editor,
+    Member,
+}
+
+impl GroupRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupRole::Owner => "owner",
+            GroupRole::Editor => "editor",
+            GroupRole::Member => "member",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "owner" => Some(GroupRole::Owner),
+            "editor" => Some(GroupRole::Editor),
+            "member" => Some(GroupRole::Member),
+            _ => None,
+        }
+    }
+
+    /// Editors and owners bring new people in
+    pub fn can_invite(&self) -> bool {
+        matches!(self, GroupRole::Owner | GroupRole::Editor)
+    }
+
+    /// Only owners change roles or remove members
+    pub fn can_manage(&self) -> bool {
+        *self == GroupRole::Owner
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Group {
+    pub id: Option<i64>,
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub member_count: i64,
+}
+
+impl Group {
+    pub fn new(slug: String, name: String, description: Option<String>) -> Self {
+        Self {
+            id: None,
+            slug,
+            name,
+            description,
+            created_at: Utc::now(),
+            member_count: 0,
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupMember {
+    pub user_id: i64,
+    pub username: String,
+    pub role: GroupRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupInvite {
+    pub id: i64,
+    pub group_id: i64,
+    pub group_slug: String,
+    pub invited_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: Option<i64>,
+    pub post_id: i64,
+    pub user_id: i64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub hidden_at: Option<DateTime<Utc>>,
+    pub author_username: Option<String>,
+}
+
+impl Comment {
+    pub fn new(post_id: i64, user_id: i64, content: String) -> Self {
+        Self {
+            id: None,
+            post_id,
+            user_id,
+            content,
+            created_at: Utc::now(),
+            hidden_at: None,
+            author_username: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: i64,
+    pub kind: String,
+    pub post_id: Option<i64>,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// A search kept under a name; with `alerts` on, new matching posts notify its owner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub query: String,
+    pub alerts: bool,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,306 @@
+// src/moderation.rs
+
+use crate::models::{
+    ModerationAction, ModerationLogEntry, Report, ReportReason, ReportTarget, UserWarning,
+};
+use crate::database::Database;
+use rusqlite::{params, Connection, OptionalExtension};
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+
+pub struct ModerationManager {
+    db: Database,
+}
+
+impl ModerationManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub fn file_report(&self, report: &mut Report) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "INSERT INTO reports (reporter_id, target_type, target_id, reason, details, status, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+        )?;
+
+        let id = stmt.insert(params![
+            report.reporter_id,
+            report.target.as_str(),
+            report.target_id,
+            report.reason.as_str(),
+            report.details,
+            report.status,
+            report.created_at.to_rfc3339()
+        ])?;
+
+        report.id = Some(id);
+        Ok(())
+    }
+
+    /// Open reports, oldest first so nothing waits forever
+    pub fn get_open_reports(&self) -> Result<Vec<Report>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT r.id, r.reporter_id, r.target_type, r.target_id, r.reason, r.details,
+                    r.status, r.created_at, u.username
+             FROM reports r
+             JOIN users u ON r.reporter_id = u.id
+             WHERE r.status = 'open'
+             ORDER BY r.created_at ASC, r.id ASC"
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(Report {
+                id: Some(row.get(0)?),
+                reporter_id: row.get(1)?,
+                target: ReportTarget::parse(&row.get::<_, String>(2)?),
+                target_id: row.get(3)?,
+                reason: ReportReason::parse(&row.get::<_, String>(4)?),
+                details: row.get(5)?,
+                status: row.get(6)?,
+                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                reporter_username: Some(row.get(8)?),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for report in rows {
+            result.push(report?);
+        }
+        Ok(result)
+    }
+
+    /// Apply `action` to the content behind `report`, close the report and
+    /// record the decision in the audit log, all in one transaction.
+    pub fn resolve_report(
+        &self,
+        report: &Report,
+        admin_id: i64,
+        action: ModerationAction,
+        note: Option<String>,
+    ) -> Result<()> {
+        let report_id = report.id.ok_or_else(|| Error::msg("Report has not been saved"))?;
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+        let now = Utc::now().to_rfc3339();
+
+        // Warnings and suspensions land on the author of a reported post or comment
+        let author_id: i64 = match report.target {
+            ReportTarget::User => report.target_id,
+            ReportTarget::Post => tx
+                .query_row(
+                    "SELECT user_id FROM posts WHERE id = ?1",
+                    params![report.target_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| Error::msg("The reported post no longer exists"))?,
+            ReportTarget::Comment => tx
+                .query_row(
+                    "SELECT user_id FROM comments WHERE id = ?1",
+                    params![report.target_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| Error::msg("The reported comment no longer exists"))?,
+        };
+
+        match action {
+            ModerationAction::Dismiss => {}
+            ModerationAction::Hide => {
+                let table = match report.target {
+                    ReportTarget::Post => "posts",
+                    ReportTarget::Comment => "comments",
+                    ReportTarget::User => return Err(Error::msg("Only posts and comments can be hidden")),
+                };
+                tx.execute(
+                    &format!("UPDATE {} SET hidden_at = ?1 WHERE id = ?2", table),
+                    params![now, report.target_id],
+                )?;
+            }
+            ModerationAction::Warn => {
+                let message = note.clone().unwrap_or_else(|| {
+                    format!("Your content was reported for {}", report.reason.as_str())
+                });
+                tx.execute(
+                    "INSERT INTO user_warnings (user_id, admin_id, message, created_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![author_id, admin_id, message, now],
+                )?;
+            }
+            ModerationAction::Suspend => {
+                if author_id == admin_id {
+                    return Err(Error::msg("You cannot suspend yourself"));
+                }
+                tx.execute(
+                    "UPDATE users SET suspended_at = ?1 WHERE id = ?2",
+                    params![now, author_id],
+                )?;
+            }
+            ModerationAction::Unsuspend | ModerationAction::GrantAdmin | ModerationAction::RevokeAdmin => {
+                return Err(Error::msg("That action is not taken on reports"));
+            }
+        }
+
+        let status = match action {
+            ModerationAction::Dismiss => "dismissed",
+            _ => "actioned",
+        };
+        tx.execute(
+            "UPDATE reports SET status = ?1 WHERE id = ?2",
+            params![status, report_id],
+        )?;
+        Self::log_action(
+            &tx,
+            Some(admin_id),
+            Some(report_id),
+            action,
+            report.target,
+            report.target_id,
+            note.as_deref(),
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Grant or revoke admin rights and log it. `admin_id` is None when run
+    /// from the server's command line, where nobody is logged in.
+    pub fn set_admin(&self, username: &str, is_admin: bool, admin_id: Option<i64>, note: Option<&str>) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let user_id = Self::user_id_for(&tx, username)?;
+        tx.execute("UPDATE users SET is_admin = ?1 WHERE id = ?2", params![is_admin, user_id])?;
+        let action = if is_admin { ModerationAction::GrantAdmin } else { ModerationAction::RevokeAdmin };
+        Self::log_action(&tx, admin_id, None, action, ReportTarget::User, user_id, note)?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Lift a suspension and log it; `admin_id` as for `set_admin`
+    pub fn lift_suspension(&self, username: &str, admin_id: Option<i64>, note: Option<&str>) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let user_id = Self::user_id_for(&tx, username)?;
+        tx.execute("UPDATE users SET suspended_at = NULL WHERE id = ?1", params![user_id])?;
+        Self::log_action(&tx, admin_id, None, ModerationAction::Unsuspend, ReportTarget::User, user_id, note)?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn user_id_for(conn: &Connection, username: &str) -> Result<i64> {
+        conn.query_row("SELECT id FROM users WHERE username = ?1", params![username], |row| row.get(0))
+            .optional()?
+            .ok_or_else(|| Error::msg(format!("User '{}' not found", username)))
+    }
+
+    /// Add an entry to the audit log, as part of the caller's transaction
+    fn log_action(
+        conn: &Connection,
+        admin_id: Option<i64>,
+        report_id: Option<i64>,
+        action: ModerationAction,
+        target: ReportTarget,
+        target_id: i64,
+        note: Option<&str>,
+    ) -> Result<()> {
+        conn.execute(
+            "INSERT INTO moderation_actions (admin_id, report_id, action, target_type, target_id, note, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                admin_id,
+                report_id,
+                action.as_str(),
+                target.as_str(),
+                target_id,
+                note,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Most recent moderation decisions first
+    pub fn get_action_log(&self, limit: i64) -> Result<Vec<ModerationLogEntry>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT m.id, COALESCE(u.username, '[no account]'), m.report_id, m.action, m.target_type,
+                    m.target_id, m.note, m.created_at
+             FROM moderation_actions m
+             LEFT JOIN users u ON m.admin_id = u.id
+             ORDER BY m.created_at DESC, m.id DESC
+             LIMIT ?1"
+        )?;
+
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(ModerationLogEntry {
+                id: row.get(0)?,
+                admin_username: row.get(1)?,
+                report_id: row.get(2)?,
+                action: row.get(3)?,
+                target: ReportTarget::parse(&row.get::<_, String>(4)?),
+                target_id: row.get(5)?,
+                note: row.get(6)?,
+                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for entry in rows {
+            result.push(entry?);
+        }
+        Ok(result)
+    }
+
+    /// Warnings the user has not seen yet; they are marked seen on return
+    pub fn take_unseen_warnings(&self, user_id: i64) -> Result<Vec<UserWarning>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, message, created_at
+             FROM user_warnings
+             WHERE user_id = ?1 AND seen_at IS NULL
+             ORDER BY created_at ASC"
+        )?;
+
+        let rows = stmt.query_map(params![user_id], |row| {
+            Ok(UserWarning {
+                id: row.get(0)?,
+                message: row.get(1)?,
+                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for warning in rows {
+            result.push(warning?);
+        }
+
+        conn.execute(
+            "UPDATE user_warnings SET seen_at = ?1 WHERE user_id = ?2 AND seen_at IS NULL",
+            params![Utc::now().to_rfc3339(), user_id],
+        )?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,104 @@
+// src/notification.rs
+
+use crate::models::Notification;
+use crate::database::Database;
+use rusqlite::{params, Connection};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+pub struct NotificationManager {
+    db: Database,
+}
+
+impl NotificationManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Queue a notification. Takes a connection so callers can fan out inside
+    /// their own transaction.
+    pub fn notify(
+        conn: &Connection,
+        user_id: i64,
+        kind: &str,
+        post_id: Option<i64>,
+        actor_id: Option<i64>,
+        message: &str,
+    ) -> Result<()> {
+        conn.execute(
+            "INSERT INTO notifications (user_id, kind, post_id, actor_id, message, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![user_id, kind, post_id, actor_id, message, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Most recent first
+    pub fn get_notifications(&self, user_id: i64, limit: i64) -> Result<Vec<Notification>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, post_id, message, created_at, read_at
+             FROM notifications
+             WHERE user_id = ?1
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?2"
+        )?;
+
+        let rows = stmt.query_map(params![user_id, limit], |row| {
+            Ok(Notification {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                post_id: row.get(2)?,
+                message: row.get(3)?,
+                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                read_at: row
+                    .get::<_, Option<String>>(5)?
+                    .map(|s| DateTime::parse_from_rfc3339(&s).unwrap().with_timezone(&Utc)),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for notification in rows {
+            result.push(notification?);
+        }
+        Ok(result)
+    }
+
+    pub fn count_unread(&self, user_id: i64) -> Result<i64> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = ?1 AND read_at IS NULL",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    pub fn mark_read(&self, user_id: i64, notification_id: i64) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        conn.execute(
+            "UPDATE notifications SET read_at = ?1 WHERE id = ?2 AND user_id = ?3 AND read_at IS NULL",
+            params![Utc::now().to_rfc3339(), notification_id, user_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn mark_all_read(&self, user_id: i64) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        conn.execute(
+            "UPDATE notifications SET read_at = ?1 WHERE user_id = ?2 AND read_at IS NULL",
+            params![Utc::now().to_rfc3339(), user_id],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+// src/pager.rs
+//
+// A small built-in pager for text longer than the screen. It drives the
+// terminal directly, so it works over any SSH PTY without `less` installed.
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, ClearType},
+};
+use crate::layout;
+use crate::session::SessionIo;
+use crate::theme::Icon;
+use std::io::{self, Write};
+
+const HELP: [&str; 6] = ["space/b page", "j/k line", "g/G top/end", "/ search", "n/N match", "q quit"];
+
+fn help() -> String {
+    HELP.join(&format!(" {} ", Icon::Separator))
+}
+
+/// Show `text` in `session` a screenful at a time. Text that fits on one
+/// screen, and any session that isn't a terminal, is written wrapped to the
+/// width.
+pub fn page(session: &mut impl SessionIo, title: &str, text: &str) -> io::Result<()> {
+    let size = if session.is_terminal() { session.size() } else { None };
+    let Some((width, height)) = size.filter(|(width, height)| *width > 0 && *height > 1) else {
+        return writeln!(session, "{}", layout::fill(text));
+    };
+
+    let mut pager = Pager::new(title, text, width, height - 1);
+    if pager.lines.len() <= pager.rows {
+        return writeln!(session, "{}", layout::fill(text));
+    }
+
+    let _guard = RawScreen::enter(session)?;
+    pager.run(session)
+}
+
+/// The session's terminal in raw mode on the alternate screen, for as long
+/// as this lives
+pub(crate) struct RawScreen {
+    out: Box<dyn Write>,
+}
+
+impl RawScreen {
+    pub(crate) fn enter(session: &impl SessionIo) -> io::Result<Self> {
+        let mut out = session
+            .screen()
+            .ok_or_else(|| io::Error::other("This session has no terminal to take over"))?;
+        terminal::enable_raw_mode()?;
+        queue!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        out.flush()?;
+        Ok(RawScreen { out })
+    }
+}
+
+impl Drop for RawScreen {
+    fn drop(&mut self) {
+        let _ = queue!(self.out, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = self.out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Pager<'a> {
+    title: &'a str,
+    text: &'a str,
+    lines: Vec<String>,
+    width: usize,
+    rows: usize, // Text rows on screen; the last screen row is the status line
+    top: usize,
+    search: Option<String>,
+    current: Option<usize>, // Line of the match last jumped to, kept apart from the scroll position
+    message: Option<String>,
+}
+
+impl<'a> Pager<'a> {
+    fn new(title: &'a str, text: &'a str, width: usize, rows: usize) -> Self {
+        let lines = layout::wrap(text, width);
+        Self { title, text, lines, width, rows, top: 0, search: None, current: None, message: None }
+    }
+
+    fn last_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.rows)
+    }
+
+    fn scroll_to(&mut self, top: usize) {
+        self.top = top.min(self.last_top());
+    }
+
+    fn run<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        loop {
+            self.draw(out)?;
+            let key = match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => key,
+                Event::Resize(width, height) => {
+                    self.width = (width as usize).max(1);
+                    self.rows = (height as usize).saturating_sub(1).max(1);
+                    self.lines = layout::wrap(self.text, self.width);
+                    self.current = None;
+                    self.scroll_to(self.top);
+                    continue;
+                }
+                _ => continue,
+            };
+            self.message = None;
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                KeyCode::Char(' ') | KeyCode::Char('f') | KeyCode::PageDown => {
+                    self.scroll_to(self.top + self.rows)
+                }
+                KeyCode::Char('b') | KeyCode::PageUp => self.scroll_to(self.top.saturating_sub(self.rows)),
+                KeyCode::Char('j') | KeyCode::Down | KeyCode::Enter => self.scroll_to(self.top + 1),
+                KeyCode::Char('k') | KeyCode::Up => self.scroll_to(self.top.saturating_sub(1)),
+                KeyCode::Char('g') | KeyCode::Home => self.top = 0,
+                KeyCode::Char('G') | KeyCode::End => self.top = self.last_top(),
+                KeyCode::Char('/') => {
+                    if let Some(pattern) = self.read_pattern(out)? {
+                        self.search = Some(pattern);
+                        self.current = None;
+                        self.find(self.top, true);
+                    }
+                }
+                KeyCode::Char('n') => self.find(self.on_screen_match().map_or(self.top, |line| line + 1), true),
+                KeyCode::Char('N') => self.find(self.on_screen_match().unwrap_or(self.top), false),
+                _ => self.message = Some(help()),
+            }
+        }
+    }
+
+    /// The current match while it is still in view; once the reader scrolls
+    /// away, n and N search from the screen instead
+    fn on_screen_match(&self) -> Option<usize> {
+        self.current.filter(|line| (self.top..self.top + self.rows).contains(line))
+    }
+
+    /// Make the next match at or after `from`, or the previous one before
+    /// it, the current match, scrolling only when it is off screen
+    fn find(&mut self, from: usize, forward: bool) {
+        let Some(pattern) = self.search.as_ref().map(|p| p.to_lowercase()) else {
+            self.message = Some("Press / to search first".to_string());
+            return;
+        };
+        let matches = |line: &String| line.to_lowercase().contains(&pattern);
+        let found = if forward {
+            self.lines.iter().enumerate().skip(from).find(|(_, line)| matches(line))
+        } else {
+            self.lines.iter().enumerate().take(from).rev().find(|(_, line)| matches(line))
+        };
+        match found {
+            Some((index, _)) => {
+                self.current = Some(index);
+                if !(self.top..self.top + self.rows).contains(&index) {
+                    self.scroll_to(index);
+                }
+            }
+            None => self.message = Some(format!("Pattern not found: {}", pattern)),
+        }
+    }
+
+    /// Read a search pattern on the status line; None when cancelled
+    fn read_pattern<W: Write>(&mut self, out: &mut W) -> io::Result<Option<String>> {
+        let mut pattern = String::new();
+        loop {
+            queue!(
+                out,
+                cursor::MoveTo(0, self.rows as u16),
+                terminal::Clear(ClearType::CurrentLine),
+                Print(format!("/{}", pattern)),
+                cursor::Show
+            )?;
+            out.flush()?;
+
+            if let Event::Key(KeyEvent { code, modifiers, kind, .. }) = event::read()? {
+                if kind == KeyEventKind::Release {
+                    continue;
+                }
+                match code {
+                    KeyCode::Enter if pattern.is_empty() => break,
+                    KeyCode::Enter => {
+                        queue!(out, cursor::Hide)?;
+                        return Ok(Some(pattern));
+                    }
+                    KeyCode::Esc => break,
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break,
+                    KeyCode::Backspace if pattern.is_empty() => break,
+                    KeyCode::Backspace => {
+                        pattern.pop();
+                    }
+                    KeyCode::Char(c) => pattern.push(c),
+                    _ => {}
+                }
+            }
+        }
+        queue!(out, cursor::Hide)?;
+        Ok(None)
+    }
+
+    fn draw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        queue!(out, terminal::Clear(ClearType::All))?;
+        let pattern = self.search.as_ref().map(|p| p.to_lowercase());
+        for (row, line) in self.lines.iter().skip(self.top).take(self.rows).enumerate() {
+            queue!(out, cursor::MoveTo(0, row as u16))?;
+            let hit = pattern.as_ref().is_some_and(|p| line.to_lowercase().contains(p.as_str()));
+            if hit && self.current == Some(self.top + row) {
+                queue!(out, SetAttribute(Attribute::Reverse), Print(line), SetAttribute(Attribute::Reset))?;
+            } else if hit {
+                queue!(out, SetAttribute(Attribute::Bold), Print(line), SetAttribute(Attribute::Reset))?;
+            } else {
+                queue!(out, Print(line))?;
+            }
+        }
+
+        let bottom = (self.top + self.rows).min(self.lines.len());
+        let status = match &self.message {
+            Some(message) => message.clone(),
+            None => format!(
+                "{} {} lines {}-{} of {} ({}%) {} q to quit",
+                self.title,
+                Icon::Separator,
+                self.top + 1,
+                bottom,
+                self.lines.len(),
+                bottom * 100 / self.lines.len().max(1),
+                Icon::Separator
+            ),
+        };
+        queue!(
+            out,
+            cursor::MoveTo(0, self.rows as u16),
+            SetAttribute(Attribute::Reverse),
+            Print(layout::truncate(&status, self.width)),
+            SetAttribute(Attribute::Reset)
+        )?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn n_walks_matches_on_the_last_screen() {
+        let text = "one\ntwo\nmatch a\nthree\nmatch b\nmatch c";
+        let mut pager = Pager::new("t", text, 20, 3);
+        pager.search = Some("match".to_string());
+
+        pager.find(pager.top, true);
+        assert_eq!((pager.current, pager.top), (Some(2), 0));
+        for expected in [4, 5] {
+            pager.find(pager.on_screen_match().map_or(pager.top, |line| line + 1), true);
+            assert_eq!((pager.current, pager.top), (Some(expected), 3));
+        }
+
+        pager.find(pager.on_screen_match().map_or(pager.top, |line| line + 1), true);
+        assert_eq!(pager.current, Some(5));
+        assert_eq!(pager.message.as_deref(), Some("Pattern not found: match"));
+
+        pager.find(pager.on_screen_match().unwrap_or(pager.top), false);
+        assert_eq!((pager.current, pager.top), (Some(4), 3));
+    }
+}
@@ -0,0 +1,203 @@
+// src/poll.rs
+
+use crate::models::{Poll, PollOption};
+use crate::database::Database;
+use rusqlite::{params, Connection};
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+
+pub struct PollManager {
+    db: Database,
+}
+
+impl PollManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Check a poll before anything is written, so a bad poll never leaves
+    /// an orphaned post behind
+    pub fn validate(poll: &Poll) -> Result<()> {
+        if poll.question.trim().is_empty() {
+            return Err(Error::msg("The poll needs a question"));
+        }
+        if poll.options.len() < Poll::MIN_OPTIONS || poll.options.len() > Poll::MAX_OPTIONS {
+            return Err(Error::msg(format!(
+                "A poll needs between {} and {} options",
+                Poll::MIN_OPTIONS,
+                Poll::MAX_OPTIONS
+            )));
+        }
+        if poll.options.iter().any(|o| o.label.trim().is_empty()) {
+            return Err(Error::msg("Poll options cannot be empty"));
+        }
+        if poll.is_closed() {
+            return Err(Error::msg("The closing time must be in the future"));
+        }
+        Ok(())
+    }
+
+    pub fn create_poll(&self, poll: &mut Poll) -> Result<()> {
+        Self::validate(poll)?;
+
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+        Self::insert_poll(&tx, poll)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Write a validated poll and its options on the caller's connection,
+    /// so it can share a transaction with the post it belongs to
+    pub fn insert_poll(conn: &Connection, poll: &mut Poll) -> Result<()> {
+        conn.execute(
+            "INSERT INTO polls (post_id, question, multiple_choice, closes_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                poll.post_id,
+                poll.question,
+                poll.multiple_choice,
+                poll.closes_at.map(|t| t.to_rfc3339()),
+                poll.created_at.to_rfc3339()
+            ],
+        )?;
+        let poll_id = conn.last_insert_rowid();
+
+        for (position, option) in poll.options.iter_mut().enumerate() {
+            conn.execute(
+                "INSERT INTO poll_options (poll_id, position, label) VALUES (?1, ?2, ?3)",
+                params![poll_id, position as i64, option.label],
+            )?;
+            option.id = Some(conn.last_insert_rowid());
+        }
+
+        poll.id = Some(poll_id);
+        Ok(())
+    }
+
+    /// The poll attached to a post, with current tallies
+    pub fn get_poll_for_post(&self, post_id: i64) -> Result<Option<Poll>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, post_id, question, multiple_choice, closes_at, created_at
+             FROM polls
+             WHERE post_id = ?1"
+        )?;
+        let mut rows = stmt.query(params![post_id])?;
+        let Some(row) = rows.next()? else {
+            return Ok(None);
+        };
+
+        let mut poll = Poll {
+            id: Some(row.get(0)?),
+            post_id: row.get(1)?,
+            question: row.get(2)?,
+            multiple_choice: row.get(3)?,
+            closes_at: row
+                .get::<_, Option<String>>(4)?
+                .map(|s| DateTime::parse_from_rfc3339(&s).unwrap().with_timezone(&Utc)),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            options: Vec::new(),
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT o.id, o.label, COUNT(v.user_id)
+             FROM poll_options o
+             LEFT JOIN poll_votes v ON v.option_id = o.id
+             WHERE o.poll_id = ?1
+             GROUP BY o.id
+             ORDER BY o.position"
+        )?;
+        let options = stmt.query_map(params![poll.id], |row| {
+            Ok(PollOption {
+                id: Some(row.get(0)?),
+                label: row.get(1)?,
+                votes: row.get(2)?,
+            })
+        })?;
+        for option in options {
+            poll.options.push(option?);
+        }
+
+        Ok(Some(poll))
+    }
+
+    pub fn has_voted(&self, poll_id: i64, user_id: i64) -> Result<bool> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let voted: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM poll_votes WHERE poll_id = ?1 AND user_id = ?2)",
+            params![poll_id, user_id],
+            |row| row.get(0),
+        )?;
+        Ok(voted)
+    }
+
+    /// Cast a user's single vote (or single set of votes for multiple choice).
+    /// `choices` are indexes into `poll.options`.
+    pub fn vote(&self, poll: &Poll, user_id: i64, choices: &[usize]) -> Result<()> {
+        let poll_id = poll.id.ok_or_else(|| Error::msg("Poll has not been saved"))?;
+        if choices.is_empty() {
+            return Err(Error::msg("Choose at least one option"));
+        }
+        if choices.len() > 1 && !poll.multiple_choice {
+            return Err(Error::msg("This poll allows only one choice"));
+        }
+
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        // Re-check inside the transaction; another session may have closed or voted
+        let (closes_at, blocked): (Option<String>, bool) = tx.query_row(
+            "SELECT q.closes_at, EXISTS (
+                 SELECT 1 FROM user_blocks b
+                 JOIN posts p ON p.user_id = b.user_id
+                 WHERE p.id = q.post_id AND b.target_user_id = ?2 AND b.kind = 'block'
+             )
+             FROM polls q WHERE q.id = ?1",
+            params![poll_id, user_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if blocked {
+            return Err(Error::msg("The author of this post has blocked you"));
+        }
+        let closed = closes_at
+            .map(|c| DateTime::parse_from_rfc3339(&c).unwrap().with_timezone(&Utc))
+            .is_some_and(|c| c <= Utc::now());
+        if closed {
+            return Err(Error::msg("This poll is closed"));
+        }
+        let voted: bool = tx.query_row(
+            "SELECT EXISTS (SELECT 1 FROM poll_votes WHERE poll_id = ?1 AND user_id = ?2)",
+            params![poll_id, user_id],
+            |row| row.get(0),
+        )?;
+        if voted {
+            return Err(Error::msg("You have already voted in this poll"));
+        }
+
+        let now = Utc::now().to_rfc3339();
+        for &choice in choices {
+            let option_id = poll
+                .options
+                .get(choice)
+                .and_then(|o| o.id)
+                .ok_or_else(|| Error::msg("Invalid option"))?;
+            tx.execute(
+                "INSERT OR IGNORE INTO poll_votes (poll_id, option_id, user_id, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![poll_id, option_id, user_id, now],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
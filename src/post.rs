@@ -1,9 +1,14 @@
 // src/post.rs
 
-use crate::models::Post;
-use crate::database::Database;
-use rusqlite::params;
+use crate::models::{ArchiveMonth, Page, PageRequest, Poll, Post, PostKind};
+use crate::poll::PollManager;
+use crate::related::RelatedManager;
+use crate::saved_search::SavedSearchManager;
+use crate::database::{Database, PostOrder, VISIBLE_POSTS_FILTER, POST_SELECT};
+use rusqlite::{params, OptionalExtension};
+use rusqlite::types::Value;
 use anyhow::{Error, Result};
+use chrono::{Datelike, NaiveDate, NaiveTime, TimeZone, Utc};
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -17,11 +22,71 @@ impl PostManager {
     }
 
     pub fn create_post(&self, post: &mut Post) -> Result<()> {
+        self.insert_post(post, None)
+    }
+
+    /// Publish a post together with its poll. Both are written in one
+    /// transaction, so a poll that fails never leaves a bare post behind
+    /// and nobody is alerted to a post that was never published.
+    pub fn create_post_with_poll(&self, post: &mut Post, poll: &mut Poll) -> Result<()> {
+        PollManager::validate(poll)?;
+        self.insert_post(post, Some(poll))
+    }
+
+    fn insert_post(&self, post: &mut Post, poll: Option<&mut Poll>) -> Result<()> {
         let conn_arc: Arc<Mutex<rusqlite::Connection>> = self.db.get_connection();
         let conn = conn_arc.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "INSERT INTO posts (user_id, title, content, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)"
+        if let Some(original_id) = post.original_post_id {
+            // A reshare of a reblog shares what the reblog repeats
+            let mut original_id = original_id;
+            let (hidden, blocked) = loop {
+                let original: Option<(String, Option<i64>, bool, bool)> = conn
+                    .query_row(
+                        "SELECT o.kind, o.original_post_id, o.hidden_at IS NOT NULL, EXISTS (
+                             SELECT 1 FROM user_blocks b
+                             WHERE b.user_id = o.user_id AND b.target_user_id = ?2 AND b.kind = 'block'
+                         )
+                         FROM posts o WHERE o.id = ?1",
+                        params![original_id, post.user_id],
+                        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                    )
+                    .optional()?;
+                match original {
+                    Some((kind, root_id, hidden, blocked)) => {
+                        if PostKind::parse(&kind) != PostKind::Reblog {
+                            break (hidden, blocked);
+                        }
+                        // A reblog whose original is gone has nothing left to share
+                        original_id = root_id.ok_or_else(|| Error::msg("The original post is unavailable"))?;
+                    }
+                    None => return Err(Error::msg("The original post is unavailable")),
+                }
+            };
+            if hidden {
+                return Err(Error::msg("The original post is unavailable"));
+            }
+            if blocked {
+                return Err(Error::msg("The author of this post has blocked you"));
+            }
+            post.original_post_id = Some(original_id);
+        }
+
+        if let Some(group_id) = post.group_id {
+            let member: bool = conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM group_members WHERE group_id = ?1 AND user_id = ?2)",
+                params![group_id, post.user_id],
+                |row| row.get(0),
+            )?;
+            if !member {
+                return Err(Error::msg("Only members can publish to this group"));
+            }
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        let mut stmt = tx.prepare(
+            "INSERT INTO posts (user_id, title, content, created_at, updated_at, kind, original_post_id, group_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
         )?;
 
         let id = stmt.insert(params![
@@ -29,77 +94,193 @@ impl PostManager {
             post.title,
             post.content,
             post.created_at.to_rfc3339(),
-            post.updated_at.to_rfc3339()
+            post.updated_at.to_rfc3339(),
+            post.kind.as_str(),
+            post.original_post_id,
+            post.group_id
         ])?;
+        drop(stmt);
+
+        if let Some(poll) = poll {
+            poll.post_id = id;
+            PollManager::insert_poll(&tx, poll)?;
+        }
+
+        // Reblogs only repeat their original, so they stay out of the index
+        // and raise no saved-search alerts
+        if post.kind != PostKind::Reblog {
+            RelatedManager::index_post(&tx, id, &post.title, &post.content)?;
+            SavedSearchManager::alert_matches(&tx, id)?;
+        }
+        tx.commit()?;
 
         post.id = Some(id);
         Ok(())
     }
 
-    pub fn get_user_posts(&self, user_id: i64) -> Result<Vec<Post>> {
+    pub fn get_post(&self, post_id: i64) -> Result<Option<Post>> {
         let conn_arc: Arc<Mutex<rusqlite::Connection>> = self.db.get_connection();
         let conn = conn_arc.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, user_id, title, content, created_at, updated_at 
-             FROM posts 
-             WHERE user_id = ?1 
-             ORDER BY created_at DESC"
-        )?;
+        let mut stmt = conn.prepare(&format!("{} WHERE p.id = ?1", POST_SELECT))?;
 
-        let rows = stmt.query_map(params![user_id], |row| {
-            Ok(Post {
-                id: Some(row.get(0)?),
-                user_id: row.get(1)?,
-                title: row.get(2)?,
-                content: row.get(3)?,
-                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
-                    .unwrap()
-                    .with_timezone(&chrono::Utc),
-                updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                    .unwrap()
-                    .with_timezone(&chrono::Utc),
-                author_username: None,
-            })
-        })?;
-
-        let mut result = Vec::new();
-        for post in rows {
-            result.push(post?);
+        let mut rows = stmt.query(params![post_id])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(Database::post_from_row(row)?))
+        } else {
+            Ok(None)
         }
-        Ok(result)
     }
 
-    pub fn get_all_posts(&self) -> Result<Vec<Post>> {
+    /// A user's own posts, newest first, including any hidden by moderators
+    pub fn get_user_posts(&self, user_id: i64, request: PageRequest, limit: usize) -> Result<Page<Post>> {
+        let conn_arc: Arc<Mutex<rusqlite::Connection>> = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        Ok(Database::query_post_page(
+            &conn,
+            "p.user_id = ?1",
+            vec![Value::Integer(user_id)],
+            PostOrder::NewestFirst,
+            request,
+            limit,
+        )?)
+    }
+
+    /// Another user's public posts, for their profile page
+    pub fn get_posts_by_author(&self, author_id: i64, request: PageRequest, limit: usize) -> Result<Page<Post>> {
+        let conn_arc: Arc<Mutex<rusqlite::Connection>> = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        Ok(Database::query_post_page(
+            &conn,
+            "p.user_id = ?1 AND p.hidden_at IS NULL",
+            vec![Value::Integer(author_id)],
+            PostOrder::NewestFirst,
+            request,
+            limit,
+        )?)
+    }
+
+    /// How many public posts an author has, as the People directory counts them
+    pub fn count_posts_by_author(&self, author_id: i64) -> Result<i64> {
         let conn_arc: Arc<Mutex<rusqlite::Connection>> = self.db.get_connection();
         let conn = conn_arc.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, user_id, title, content, created_at, updated_at 
-             FROM posts 
-             ORDER BY created_at DESC"
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM posts WHERE user_id = ?1 AND hidden_at IS NULL",
+            params![author_id],
+            |row| row.get(0),
         )?;
+        Ok(count)
+    }
+
+    /// A group blog's posts, minus anything the viewer has hidden
+    pub fn get_group_posts(
+        &self,
+        group_id: i64,
+        viewer_id: i64,
+        request: PageRequest,
+        limit: usize,
+    ) -> Result<Page<Post>> {
+        let conn_arc: Arc<Mutex<rusqlite::Connection>> = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
 
-        let rows = stmt.query_map([], |row| {
-            Ok(Post {
-                id: Some(row.get(0)?),
-                user_id: row.get(1)?,
-                title: row.get(2)?,
-                content: row.get(3)?,
-                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
-                    .unwrap()
-                    .with_timezone(&chrono::Utc),
-                updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                    .unwrap()
-                    .with_timezone(&chrono::Utc),
-                author_username: None,
+        Ok(Database::query_post_page(
+            &conn,
+            &format!("p.group_id = ?2 AND {}", VISIBLE_POSTS_FILTER),
+            vec![Value::Integer(viewer_id), Value::Integer(group_id)],
+            PostOrder::NewestFirst,
+            request,
+            limit,
+        )?)
+    }
+
+    /// Every post the viewer has not hidden by muting or blocking its author
+    pub fn get_all_posts(&self, viewer_id: i64, request: PageRequest, limit: usize) -> Result<Page<Post>> {
+        let conn_arc: Arc<Mutex<rusqlite::Connection>> = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        Ok(Database::query_post_page(
+            &conn,
+            VISIBLE_POSTS_FILTER,
+            vec![Value::Integer(viewer_id)],
+            PostOrder::NewestFirst,
+            request,
+            limit,
+        )?)
+    }
+
+    /// Post counts per month, newest month first, for the whole site or one
+    /// author. Counted in SQL from the `YYYY-MM` prefix of `created_at`,
+    /// which is always stored in UTC.
+    pub fn get_archive(&self, viewer_id: i64, author_id: Option<i64>) -> Result<Vec<ArchiveMonth>> {
+        let conn_arc: Arc<Mutex<rusqlite::Connection>> = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT CAST(substr(p.created_at, 1, 4) AS INTEGER),
+                    CAST(substr(p.created_at, 6, 2) AS INTEGER),
+                    COUNT(*)
+             FROM posts p
+             WHERE {} AND (?2 IS NULL OR p.user_id = ?2)
+             GROUP BY substr(p.created_at, 1, 7)
+             ORDER BY substr(p.created_at, 1, 7) DESC",
+            VISIBLE_POSTS_FILTER
+        ))?;
+
+        let rows = stmt.query_map(params![viewer_id, author_id], |row| {
+            Ok(ArchiveMonth {
+                year: row.get(0)?,
+                month: row.get(1)?,
+                count: row.get(2)?,
             })
         })?;
 
         let mut result = Vec::new();
-        for post in rows {
-            result.push(post?);
+        for month in rows {
+            result.push(month?);
         }
         Ok(result)
     }
+
+    /// The posts of one archive month, oldest first
+    pub fn get_month_posts(
+        &self,
+        viewer_id: i64,
+        author_id: Option<i64>,
+        month: &ArchiveMonth,
+        request: PageRequest,
+        limit: usize,
+    ) -> Result<Page<Post>> {
+        let start = NaiveDate::from_ymd_opt(month.year, month.month, 1)
+            .ok_or_else(|| Error::msg("Invalid month"))?;
+        let end = if start.month() == 12 {
+            NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+        }
+        .ok_or_else(|| Error::msg("Invalid month"))?;
+        let bound = |date: NaiveDate| Value::Text(Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN)).to_rfc3339());
+
+        let conn_arc: Arc<Mutex<rusqlite::Connection>> = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        Ok(Database::query_post_page(
+            &conn,
+            &format!(
+                "{} AND (?2 IS NULL OR p.user_id = ?2) AND p.created_at >= ?3 AND p.created_at < ?4",
+                VISIBLE_POSTS_FILTER
+            ),
+            vec![
+                Value::Integer(viewer_id),
+                author_id.map_or(Value::Null, Value::Integer),
+                bound(start),
+                bound(end),
+            ],
+            PostOrder::OldestFirst,
+            request,
+            limit,
+        )?)
+    }
 }
@@ -0,0 +1,469 @@
+// src/query.rs
+//
+// The search query language: plain words, "quoted phrases", filters such as
+// author:alice, before:2026-01-01, after:2026-01-01 and in:title, and '-' in
+// front of any of them to negate it. Queries are parsed into a `Query` and
+// compiled to SQL whose every user-supplied value is a bound parameter.
+
+use crate::layout;
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use rusqlite::types::Value;
+use std::fmt;
+
+/// Where free-text terms are looked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    All,
+    Title,
+    Body,
+}
+
+impl Scope {
+    fn fts_column(&self) -> Option<&'static str> {
+        match self {
+            Scope::All => None,
+            Scope::Title => Some("title"),
+            Scope::Body => Some("content"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Word(String),
+    Phrase(String),
+    Author(String),
+    Before(NaiveDate),
+    After(NaiveDate),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clause {
+    pub negated: bool,
+    pub term: Term,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    pub clauses: Vec<Clause>,
+    pub scope: Scope,
+}
+
+/// A query that could not be parsed, pointing at the offending token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub position: usize, // In characters from the start of the query
+    pub token: String,
+    pub message: String,
+}
+
+impl QueryError {
+    fn new(position: usize, token: &str, message: impl Into<String>) -> Self {
+        Self { position, token: token.to_string(), message: message.into() }
+    }
+
+    /// The query with a caret line under the bad token. Columns are
+    /// measured in display width, so wide characters before the token don't
+    /// push the caret out of line.
+    pub fn pointer(&self, query: &str) -> String {
+        let prefix: String = query.chars().take(self.position).collect();
+        let indent = layout::display_width(&prefix);
+        let width = layout::display_width(&self.token).max(1);
+        format!("{}\n{}{}", query, " ".repeat(indent), "^".repeat(width))
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// SQL conditions over `p` (see `POST_SELECT`) and the values they bind
+#[derive(Debug, Clone)]
+pub struct CompiledQuery {
+    /// Conditions joined with AND; "1" when the query only has text to match
+    pub filter: String,
+    pub params: Vec<Value>,
+    /// Placeholder number of the FTS5 expression for the positive text
+    /// terms, used to rank results; None when there are none
+    pub rank_match: Option<usize>,
+}
+
+struct Token<'a> {
+    position: usize,
+    text: &'a str,
+    quoted: bool,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token<'_>>, QueryError> {
+    let mut tokens = Vec::new();
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].1.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut quoted = false;
+        while i < chars.len() && !chars[i].1.is_whitespace() {
+            if chars[i].1 == '"' {
+                // A quote runs to the closing quote, spaces included
+                let open = i;
+                i += 1;
+                while i < chars.len() && chars[i].1 != '"' {
+                    i += 1;
+                }
+                if i == chars.len() {
+                    return Err(QueryError::new(open, "\"", "This quote is never closed"));
+                }
+                quoted = true;
+            }
+            i += 1;
+        }
+        let byte_start = chars[start].0;
+        let byte_end = chars.get(i).map_or(input.len(), |(b, _)| *b);
+        tokens.push(Token { position: start, text: &input[byte_start..byte_end], quoted });
+    }
+    Ok(tokens)
+}
+
+fn parse_date(value: &str, position: usize, token: &str) -> Result<NaiveDate, QueryError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        QueryError::new(position, token, format!("'{}' is not a date; use YYYY-MM-DD", value))
+    })
+}
+
+fn unquote(value: &str) -> &str {
+    value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value)
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query, QueryError> {
+        let mut clauses = Vec::new();
+        let mut scope: Option<(Scope, usize)> = None;
+
+        for token in tokenize(input)? {
+            let (negated, body) = match token.text.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, token.text),
+            };
+            if body.is_empty() {
+                return Err(QueryError::new(token.position, token.text, "'-' must be followed by a word or filter"));
+            }
+
+            let term = if body.starts_with('"') {
+                let phrase = unquote(body).trim();
+                if phrase.is_empty() {
+                    return Err(QueryError::new(token.position, token.text, "This phrase is empty"));
+                }
+                Term::Phrase(phrase.to_string())
+            } else if let Some((key, value)) = body.split_once(':') {
+                let value = unquote(value);
+                if value.is_empty() {
+                    return Err(QueryError::new(
+                        token.position,
+                        token.text,
+                        format!("{}: needs a value, e.g. {}", key, example_for(key)),
+                    ));
+                }
+                match key {
+                    "author" | "by" => Term::Author(value.trim_start_matches('@').to_string()),
+                    "before" => Term::Before(parse_date(value, token.position, token.text)?),
+                    "after" => Term::After(parse_date(value, token.position, token.text)?),
+                    "in" => {
+                        let new_scope = match value {
+                            "title" => Scope::Title,
+                            "body" | "content" => Scope::Body,
+                            _ => {
+                                return Err(QueryError::new(
+                                    token.position,
+                                    token.text,
+                                    format!("in:{} is not a place to search; use in:title or in:body", value),
+                                ));
+                            }
+                        };
+                        if negated {
+                            return Err(QueryError::new(token.position, token.text, "in: cannot be negated"));
+                        }
+                        if let Some((existing, _)) = scope
+                            && existing != new_scope
+                        {
+                            return Err(QueryError::new(
+                                token.position,
+                                token.text,
+                                "Only one in: filter can be used per search",
+                            ));
+                        }
+                        scope = Some((new_scope, token.position));
+                        continue;
+                    }
+                    "tag" => {
+                        return Err(QueryError::new(token.position, token.text, "Posts don't have tags yet"));
+                    }
+                    "is" if value == "draft" => {
+                        return Err(QueryError::new(token.position, token.text, "Drafts aren't supported yet"));
+                    }
+                    "is" => {
+                        return Err(QueryError::new(
+                            token.position,
+                            token.text,
+                            format!("is:{} is not something a post can be", value),
+                        ));
+                    }
+                    _ => {
+                        return Err(QueryError::new(
+                            token.position,
+                            token.text,
+                            format!(
+                                "Unknown filter '{}:'; try author:, before:, after: or in:, or quote it to search for the text",
+                                key
+                            ),
+                        ));
+                    }
+                }
+            } else if token.quoted {
+                return Err(QueryError::new(
+                    token.position,
+                    token.text,
+                    "Put quotes around the whole phrase, e.g. \"exact words\"",
+                ));
+            } else {
+                Term::Word(body.to_string())
+            };
+
+            clauses.push(Clause { negated, term });
+        }
+
+        if clauses.is_empty() {
+            let position = scope.map_or(0, |(_, position)| position);
+            return Err(QueryError::new(position, input.trim(), "Enter something to search for"));
+        }
+
+        Ok(Query { clauses, scope: scope.map_or(Scope::All, |(scope, _)| scope) })
+    }
+
+    /// Compile to SQL conditions whose placeholders start at `?{first_param}`
+    pub fn compile(&self, first_param: usize) -> CompiledQuery {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Value> = Vec::new();
+        let next = |params: &mut Vec<Value>, value: Value| {
+            params.push(value);
+            first_param + params.len() - 1
+        };
+
+        let positive: Vec<&Term> = self
+            .clauses
+            .iter()
+            .filter(|c| !c.negated && matches!(c.term, Term::Word(_) | Term::Phrase(_)))
+            .map(|c| &c.term)
+            .collect();
+        let rank_match = if positive.is_empty() {
+            None
+        } else {
+            Some(next(&mut params, Value::Text(self.fts_expression(&positive))))
+        };
+
+        for clause in &self.clauses {
+            let condition = match &clause.term {
+                // Positive text terms are matched through the ranking join
+                Term::Word(_) | Term::Phrase(_) if !clause.negated => continue,
+                Term::Word(_) | Term::Phrase(_) => {
+                    let n = next(&mut params, Value::Text(self.fts_expression(&[&clause.term])));
+                    conditions.push(format!(
+                        "p.id NOT IN (SELECT rowid FROM posts_fts WHERE posts_fts MATCH ?{})",
+                        n
+                    ));
+                    continue;
+                }
+                Term::Author(username) => {
+                    let n = next(&mut params, Value::Text(username.clone()));
+                    format!(
+                        "(u.username = ?{n} OR p.user_id IN (SELECT user_id FROM username_aliases WHERE alias = ?{n}))"
+                    )
+                }
+                Term::Before(date) => {
+                    let n = next(&mut params, Value::Text(start_of_day(*date)));
+                    format!("p.created_at < ?{}", n)
+                }
+                Term::After(date) => {
+                    let n = next(&mut params, Value::Text(start_of_day(*date)));
+                    format!("p.created_at >= ?{}", n)
+                }
+            };
+            if clause.negated {
+                conditions.push(format!("NOT {}", condition));
+            } else {
+                conditions.push(condition);
+            }
+        }
+
+        CompiledQuery {
+            filter: if conditions.is_empty() { "1".to_string() } else { conditions.join(" AND ") },
+            params,
+            rank_match,
+        }
+    }
+
+    /// An FTS5 expression requiring every term, within the query's scope.
+    /// Terms are always quoted, so nothing typed is read as FTS5 syntax.
+    fn fts_expression(&self, terms: &[&Term]) -> String {
+        let parts: Vec<String> = terms
+            .iter()
+            .filter_map(|term| match term {
+                Term::Word(word) => Some(format!("\"{}\"*", word.replace('"', "\"\""))),
+                Term::Phrase(phrase) => Some(format!("\"{}\"", phrase.replace('"', "\"\""))),
+                _ => None,
+            })
+            .collect();
+        let expression = parts.join(" ");
+        match self.scope.fts_column() {
+            Some(column) => format!("{{{}}} : ({})", column, expression),
+            None => expression,
+        }
+    }
+}
+
+fn start_of_day(date: NaiveDate) -> String {
+    Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN)).to_rfc3339()
+}
+
+fn example_for(key: &str) -> &'static str {
+    match key {
+        "author" | "by" => "author:alice",
+        "before" => "before:2026-01-01",
+        "after" => "after:2026-01-01",
+        "in" => "in:title",
+        _ => "author:alice",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> Term {
+        Term::Word(text.to_string())
+    }
+
+    #[test]
+    fn parses_words_phrases_and_filters() {
+        let query = Query::parse(r#"rust "exact words" author:@alice after:2026-01-01"#).unwrap();
+        assert_eq!(query.scope, Scope::All);
+        assert_eq!(
+            query.clauses,
+            vec![
+                Clause { negated: false, term: word("rust") },
+                Clause { negated: false, term: Term::Phrase("exact words".to_string()) },
+                Clause { negated: false, term: Term::Author("alice".to_string()) },
+                Clause { negated: false, term: Term::After(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()) },
+            ]
+        );
+    }
+
+    #[test]
+    fn minus_negates_words_phrases_and_filters() {
+        let query = Query::parse(r#"-draft -"two words" -by:bob in:title"#).unwrap();
+        assert_eq!(query.scope, Scope::Title);
+        assert!(query.clauses.iter().all(|c| c.negated));
+        assert_eq!(query.clauses[2].term, Term::Author("bob".to_string()));
+    }
+
+    #[test]
+    fn lone_minus_is_an_error() {
+        let error = Query::parse("rust -").unwrap_err();
+        assert_eq!((error.position, error.token.as_str()), (5, "-"));
+    }
+
+    #[test]
+    fn unclosed_quote_points_at_the_quote() {
+        let error = Query::parse(r#"rust "never closed"#).unwrap_err();
+        assert_eq!(error.position, 5);
+        assert_eq!(error.message, "This quote is never closed");
+    }
+
+    #[test]
+    fn quote_inside_a_word_is_rejected() {
+        let error = Query::parse(r#"say"hello""#).unwrap_err();
+        assert_eq!(error.position, 0);
+        assert!(error.message.starts_with("Put quotes around the whole phrase"));
+    }
+
+    #[test]
+    fn conflicting_scopes_are_rejected() {
+        assert_eq!(Query::parse("rust in:title in:title").unwrap().scope, Scope::Title);
+
+        let error = Query::parse("rust in:title in:body").unwrap_err();
+        assert_eq!((error.position, error.token.as_str()), (14, "in:body"));
+        assert_eq!(error.message, "Only one in: filter can be used per search");
+
+        let error = Query::parse("rust -in:title").unwrap_err();
+        assert_eq!(error.message, "in: cannot be negated");
+    }
+
+    #[test]
+    fn bad_dates_name_the_format() {
+        let error = Query::parse("before:2026-13-01").unwrap_err();
+        assert_eq!((error.position, error.token.as_str()), (0, "before:2026-13-01"));
+        assert_eq!(error.message, "'2026-13-01' is not a date; use YYYY-MM-DD");
+
+        let error = Query::parse("rust after:").unwrap_err();
+        assert_eq!(error.message, "after: needs a value, e.g. after:2026-01-01");
+    }
+
+    #[test]
+    fn scope_alone_is_not_a_search() {
+        let error = Query::parse("  in:body").unwrap_err();
+        assert_eq!(error.position, 2);
+        assert_eq!(error.message, "Enter something to search for");
+    }
+
+    #[test]
+    fn compile_numbers_placeholders_from_the_first_param() {
+        let query = Query::parse("rust -java author:alice before:2026-02-01").unwrap();
+        let compiled = query.compile(3);
+
+        assert_eq!(compiled.rank_match, Some(3));
+        assert_eq!(
+            compiled.filter,
+            "p.id NOT IN (SELECT rowid FROM posts_fts WHERE posts_fts MATCH ?4) AND \
+             (u.username = ?5 OR p.user_id IN (SELECT user_id FROM username_aliases WHERE alias = ?5)) AND \
+             p.created_at < ?6"
+        );
+        assert_eq!(
+            compiled.params,
+            vec![
+                Value::Text("\"rust\"*".to_string()),
+                Value::Text("\"java\"*".to_string()),
+                Value::Text("alice".to_string()),
+                Value::Text("2026-02-01T00:00:00+00:00".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn compile_without_text_has_no_rank_match() {
+        let compiled = Query::parse("-after:2026-01-01 in:body").unwrap().compile(1);
+        assert_eq!(compiled.rank_match, None);
+        assert_eq!(compiled.filter, "NOT p.created_at >= ?1");
+        assert_eq!(compiled.params.len(), 1);
+    }
+
+    #[test]
+    fn scope_is_applied_to_the_fts_expression() {
+        let compiled = Query::parse(r#"in:title rust "big crab""#).unwrap().compile(1);
+        assert_eq!(compiled.params[0], Value::Text(r#"{title} : ("rust"* "big crab")"#.to_string()));
+    }
+
+    #[test]
+    fn pointer_lines_up_under_wide_characters() {
+        let input = "日本 before:soon";
+        let error = Query::parse(input).unwrap_err();
+        assert_eq!(error.position, 3);
+        assert_eq!(error.pointer(input), "日本 before:soon\n     ^^^^^^^^^^^");
+    }
+}
@@ -0,0 +1,320 @@
+// src/ranking.rs
+
+use crate::models::Post;
+use crate::database::{Database, POST_SELECT, VISIBLE_POSTS_FILTER};
+use rusqlite::{params, Connection};
+use anyhow::{Error, Result};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// How long computed scores are served before the next refresh
+const REFRESH_INTERVAL: Duration = Duration::minutes(15);
+/// Activity older than this no longer counts towards trending
+const TRENDING_WINDOW_DAYS: i64 = 7;
+
+/// Weights of each kind of engagement and how fast it fades. Posts have no
+/// reactions, so reblogs and quotes count in their place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankingFormula {
+    pub view_weight: f64,
+    pub comment_weight: f64,
+    pub bookmark_weight: f64,
+    pub reshare_weight: f64,
+    /// After this many hours an interaction counts half as much for trending
+    pub half_life_hours: f64,
+}
+
+impl Default for RankingFormula {
+    fn default() -> Self {
+        Self {
+            view_weight: 1.0,
+            comment_weight: 4.0,
+            bookmark_weight: 3.0,
+            reshare_weight: 5.0,
+            half_life_hours: 48.0,
+        }
+    }
+}
+
+impl RankingFormula {
+    fn weight(&self, kind: &str) -> f64 {
+        match kind {
+            "view" => self.view_weight,
+            "comment" => self.comment_weight,
+            "bookmark" => self.bookmark_weight,
+            "reshare" => self.reshare_weight,
+            _ => 0.0,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let weights = [self.view_weight, self.comment_weight, self.bookmark_weight, self.reshare_weight];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err(Error::msg("Weights must be zero or more"));
+        }
+        if !self.half_life_hours.is_finite() || self.half_life_hours <= 0.0 {
+            return Err(Error::msg("The half-life must be more than zero hours"));
+        }
+        Ok(())
+    }
+}
+
+/// A post and the score it was ranked by
+#[derive(Debug, Clone)]
+pub struct RankedPost {
+    pub post: Post,
+    pub score: f64,
+}
+
+/// Every interaction that counts, as (post_id, kind, created_at). Reshares
+/// count for the original post; nobody's activity on their own posts counts.
+const ENGAGEMENT: &str =
+    "SELECT v.post_id, 'view' AS kind, v.viewed_at AS created_at
+     FROM post_views v JOIN posts p ON p.id = v.post_id
+     WHERE v.user_id != p.user_id
+     UNION ALL
+     SELECT c.post_id, 'comment', c.created_at
+     FROM comments c JOIN posts p ON p.id = c.post_id
+     WHERE c.user_id != p.user_id AND c.hidden_at IS NULL
+     UNION ALL
+     SELECT b.post_id, 'bookmark', b.created_at
+     FROM bookmarks b JOIN posts p ON p.id = b.post_id
+     WHERE b.user_id != p.user_id
+     UNION ALL
+     SELECT r.original_post_id, 'reshare', r.created_at
+     FROM posts r JOIN posts p ON p.id = r.original_post_id
+     WHERE r.user_id != p.user_id";
+
+/// Popularity scores, kept in `post_scores` and recomputed at most every
+/// REFRESH_INTERVAL rather than on each request. All-time scores are plain
+/// weighted sums; trending scores halve every `half_life_hours` and only
+/// look at the last TRENDING_WINDOW_DAYS.
+pub struct RankingManager {
+    db: Database,
+}
+
+impl RankingManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Count a view; each reader counts once per post
+    pub fn record_view(&self, user_id: i64, post_id: i64) -> Result<()> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        conn.execute(
+            "INSERT OR IGNORE INTO post_views (post_id, user_id, viewed_at) VALUES (?1, ?2, ?3)",
+            params![post_id, user_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_formula(&self) -> Result<RankingFormula> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+        Self::load_formula(&conn)
+    }
+
+    /// Change the formula; scores are recomputed with it straight away
+    pub fn set_formula(&self, formula: &RankingFormula) -> Result<()> {
+        formula.validate()?;
+
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        conn.execute(
+            "UPDATE ranking_formula
+             SET view_weight = ?1, comment_weight = ?2, bookmark_weight = ?3, reshare_weight = ?4,
+                 half_life_hours = ?5, refreshed_at = NULL
+             WHERE id = 1",
+            params![
+                formula.view_weight,
+                formula.comment_weight,
+                formula.bookmark_weight,
+                formula.reshare_weight,
+                formula.half_life_hours,
+            ],
+        )?;
+        Self::refresh_if_due(&conn)?;
+        Ok(())
+    }
+
+    /// Posts with the most recent engagement, best first
+    pub fn get_trending(&self, viewer_id: i64, limit: i64) -> Result<Vec<RankedPost>> {
+        self.get_ranked(viewer_id, "trending", limit)
+    }
+
+    /// Posts with the most engagement ever, best first
+    pub fn get_all_time_top(&self, viewer_id: i64, limit: i64) -> Result<Vec<RankedPost>> {
+        self.get_ranked(viewer_id, "all_time", limit)
+    }
+
+    fn get_ranked(&self, viewer_id: i64, column: &str, limit: i64) -> Result<Vec<RankedPost>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+        Self::refresh_if_due(&conn)?;
+
+        // The score is the last column, after those of POST_SELECT
+        let mut stmt = conn.prepare(&format!(
+            "SELECT r.*, s.{column}
+             FROM ({} WHERE p.kind != 'reblog' AND {}) r
+             JOIN post_scores s ON s.post_id = r.id
+             WHERE s.{column} > 0
+             ORDER BY s.{column} DESC, r.created_at DESC
+             LIMIT ?2",
+            POST_SELECT, VISIBLE_POSTS_FILTER
+        ))?;
+
+        let rows = stmt.query_map(params![viewer_id, limit], |row| {
+            Ok(RankedPost {
+                post: Database::post_from_row(row)?,
+                score: row.get(14)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for ranked in rows {
+            result.push(ranked?);
+        }
+        Ok(result)
+    }
+
+    fn load_formula(conn: &Connection) -> Result<RankingFormula> {
+        Ok(conn.query_row(
+            "SELECT view_weight, comment_weight, bookmark_weight, reshare_weight, half_life_hours
+             FROM ranking_formula WHERE id = 1",
+            [],
+            |row| {
+                Ok(RankingFormula {
+                    view_weight: row.get(0)?,
+                    comment_weight: row.get(1)?,
+                    bookmark_weight: row.get(2)?,
+                    reshare_weight: row.get(3)?,
+                    half_life_hours: row.get(4)?,
+                })
+            },
+        )?)
+    }
+
+    /// Recompute every score if the last refresh is older than
+    /// REFRESH_INTERVAL. Sessions call this as they go, and whichever gets
+    /// there first does the work. Returns true if it did.
+    pub fn refresh_if_due(conn: &Connection) -> Result<bool> {
+        let now = Utc::now();
+        let due_before = (now - REFRESH_INTERVAL).to_rfc3339();
+        let due: bool = conn.query_row(
+            "SELECT refreshed_at IS NULL OR refreshed_at < ?1 FROM ranking_formula WHERE id = 1",
+            params![due_before],
+            |row| row.get(0),
+        )?;
+        if !due {
+            return Ok(false);
+        }
+
+        // Claim the refresh with a single statement, so only one session runs
+        // it and no write lock is held while the scores are worked out
+        let claimed = conn.execute(
+            "UPDATE ranking_formula SET refreshed_at = ?1
+             WHERE id = 1 AND (refreshed_at IS NULL OR refreshed_at < ?2)",
+            params![now.to_rfc3339(), due_before],
+        )?;
+        if claimed == 0 {
+            return Ok(false);
+        }
+
+        let result = Self::load_formula(conn)
+            .and_then(|formula| Self::compute(conn, &formula, now))
+            .and_then(|scores| Self::store(conn, &scores));
+        if result.is_err() {
+            // Give the claim back so the next session tries again
+            conn.execute(
+                "UPDATE ranking_formula SET refreshed_at = NULL WHERE id = 1 AND refreshed_at = ?1",
+                params![now.to_rfc3339()],
+            )?;
+        }
+        result.map(|_| true)
+    }
+
+    /// Read every post's (trending, all-time) score; nothing is written
+    fn compute(
+        conn: &Connection,
+        formula: &RankingFormula,
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<i64, (f64, f64)>> {
+        let mut scores: HashMap<i64, (f64, f64)> = HashMap::new();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT e.post_id,
+                    SUM(CASE e.kind WHEN 'view' THEN ?1 WHEN 'comment' THEN ?2
+                                    WHEN 'bookmark' THEN ?3 ELSE ?4 END)
+             FROM ({}) e
+             GROUP BY e.post_id",
+            ENGAGEMENT
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                formula.view_weight,
+                formula.comment_weight,
+                formula.bookmark_weight,
+                formula.reshare_weight,
+            ],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?)),
+        )?;
+        for row in rows {
+            let (post_id, all_time) = row?;
+            scores.entry(post_id).or_default().1 = all_time;
+        }
+
+        // Decay is applied per hour of activity, so only the week's buckets
+        // are read no matter how much happened
+        let since = (now - Duration::days(TRENDING_WINDOW_DAYS)).to_rfc3339();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT e.post_id, e.kind, substr(e.created_at, 1, 13), COUNT(*)
+             FROM ({}) e
+             WHERE e.created_at >= ?1
+             GROUP BY e.post_id, e.kind, substr(e.created_at, 1, 13)",
+            ENGAGEMENT
+        ))?;
+        let rows = stmt.query_map(params![since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+
+        for row in rows {
+            let (post_id, kind, hour, count) = row?;
+            let Ok(hour) = DateTime::parse_from_rfc3339(&format!("{}:30:00+00:00", hour)) else {
+                continue;
+            };
+            let age_hours = (now - hour.with_timezone(&Utc)).num_minutes().max(0) as f64 / 60.0;
+            let decay = 0.5_f64.powf(age_hours / formula.half_life_hours);
+            scores.entry(post_id).or_default().0 += formula.weight(&kind) * count as f64 * decay;
+        }
+        Ok(scores)
+    }
+
+    /// Replace the stored scores in one short transaction
+    fn store(conn: &Connection, scores: &HashMap<i64, (f64, f64)>) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        // Start from zero so posts whose engagement has gone drop out too
+        tx.execute("UPDATE post_scores SET trending = 0, all_time = 0", [])?;
+
+        // Posts deleted since the scores were read are skipped
+        let mut upsert = tx.prepare(
+            "INSERT INTO post_scores (post_id, trending, all_time)
+             SELECT ?1, ?2, ?3 WHERE EXISTS (SELECT 1 FROM posts WHERE id = ?1)
+             ON CONFLICT(post_id) DO UPDATE SET trending = excluded.trending, all_time = excluded.all_time",
+        )?;
+        for (post_id, (trending, all_time)) in scores {
+            upsert.execute(params![post_id, trending, all_time])?;
+        }
+        drop(upsert);
+
+        tx.commit()?;
+        Ok(())
+    }
+}
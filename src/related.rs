@@ -0,0 +1,205 @@
+// src/related.rs
+//
+// "Related posts" from a term index kept in SQLite: each post's terms with
+// their normalised frequency (`post_terms`) and how many posts use each term
+// (`term_stats`, kept up to date by triggers). Posts are indexed when they
+// are written; anything the index has missed or that changed since is caught
+// up at startup and by each session's watcher, off the reading path.
+
+use crate::models::Post;
+use crate::database::{Database, POST_SELECT, VISIBLE_POSTS_FILTER};
+use rusqlite::{params, params_from_iter, Connection};
+use rusqlite::types::Value;
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashMap;
+
+/// Terms of the current post used to look for others, by TF-IDF weight
+const QUERY_TERMS: usize = 16;
+/// Text matches considered before visibility filtering and the author boost
+const CANDIDATES: usize = 50;
+/// Added to the similarity of posts by the same author. Posts have no tags
+/// yet, so authorship is the only signal besides the text.
+const SAME_AUTHOR_BOOST: f64 = 0.05;
+/// Words too common to say anything about a post
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "again", "also", "and", "any", "are", "because", "been", "before", "being", "but",
+    "can", "could", "did", "does", "doing", "for", "from", "had", "has", "have", "her", "here", "his",
+    "how", "into", "its", "just", "more", "most", "not", "now", "off", "once", "only", "other", "our",
+    "out", "over", "own", "same", "she", "should", "some", "such", "than", "that", "the", "their",
+    "them", "then", "there", "these", "they", "this", "those", "through", "too", "under", "until",
+    "very", "was", "were", "what", "when", "where", "which", "while", "who", "why", "will", "with",
+    "would", "you", "your",
+];
+
+/// Lowercased words of three or more letters or digits, minus stop words.
+/// Title words count twice.
+pub fn terms(title: &str, content: &str) -> HashMap<String, f64> {
+    let mut counts: HashMap<String, f64> = HashMap::new();
+    for (text, weight) in [(title, 2.0), (content, 1.0)] {
+        for word in text.split(|c: char| !c.is_alphanumeric()) {
+            if word.chars().count() < 3 {
+                continue;
+            }
+            let word = word.to_lowercase();
+            if STOP_WORDS.contains(&word.as_str()) {
+                continue;
+            }
+            *counts.entry(word).or_default() += weight;
+        }
+    }
+
+    // Unit length, so long posts don't match everything
+    let norm = counts.values().map(|c| c * c).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for count in counts.values_mut() {
+            *count /= norm;
+        }
+    }
+    counts
+}
+
+pub struct RelatedManager {
+    db: Database,
+}
+
+impl RelatedManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Replace a post's entry in the term index. Call it in the transaction
+    /// that writes the post.
+    pub fn index_post(conn: &Connection, post_id: i64, title: &str, content: &str) -> Result<()> {
+        conn.execute("DELETE FROM post_terms WHERE post_id = ?1", params![post_id])?;
+
+        let mut insert = conn.prepare_cached("INSERT INTO post_terms (term, post_id, weight) VALUES (?1, ?2, ?3)")?;
+        for (term, weight) in terms(title, content) {
+            insert.execute(params![term, post_id, weight])?;
+        }
+        conn.execute(
+            "INSERT OR REPLACE INTO post_term_index (post_id, indexed_at) VALUES (?1, ?2)",
+            params![post_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Index posts that are new or changed since they were last indexed.
+    /// Deleted posts leave the index by cascade. Returns how many were indexed.
+    pub fn catch_up(conn: &Connection) -> Result<usize> {
+        let mut stmt = conn.prepare(
+            "SELECT p.id, p.title, p.content
+             FROM posts p
+             LEFT JOIN post_term_index i ON i.post_id = p.id
+             WHERE p.kind != 'reblog' AND (i.post_id IS NULL OR i.indexed_at < p.updated_at)",
+        )?;
+        let stale: Vec<(i64, String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        for (post_id, title, content) in &stale {
+            Self::index_post(&tx, *post_id, title, content)?;
+        }
+        tx.commit()?;
+        Ok(stale.len())
+    }
+
+    /// Up to `limit` posts most like `post`, by TF-IDF similarity plus a
+    /// small boost for sharing its author, among those the viewer can see
+    pub fn get_related(&self, post: &Post, viewer_id: i64, limit: usize) -> Result<Vec<Post>> {
+        let Some(post_id) = post.id else {
+            return Ok(Vec::new());
+        };
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let indexed: f64 = conn.query_row("SELECT COUNT(*) FROM post_term_index", [], |row| row.get(0))?;
+
+        // The post's most telling terms, weighted by inverse document frequency
+        let mut stmt = conn.prepare(
+            "SELECT t.term, t.weight, s.posts
+             FROM post_terms t JOIN term_stats s ON s.term = t.term
+             WHERE t.post_id = ?1",
+        )?;
+        let post_terms: Vec<(String, f64, f64)> = stmt
+            .query_map(params![post_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+        let mut query: Vec<(String, f64)> = post_terms
+            .into_iter()
+            // A term on more than half the posts says nothing, and has the longest posting list
+            .filter(|(_, _, posts)| *posts > 1.0 && *posts * 2.0 <= indexed)
+            .map(|(term, weight, posts)| {
+                let idf = (indexed / posts).ln();
+                (term, weight * idf * idf)
+            })
+            .collect();
+        query.sort_by(|a, b| b.1.total_cmp(&a.1));
+        query.truncate(QUERY_TERMS);
+
+        let mut scores: HashMap<i64, f64> = HashMap::new();
+        let mut postings = conn.prepare("SELECT post_id, weight FROM post_terms WHERE term = ?1 AND post_id != ?2")?;
+        for (term, weight) in &query {
+            let rows = postings.query_map(params![term, post_id], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?))
+            })?;
+            for row in rows {
+                let (other_id, other_weight) = row?;
+                *scores.entry(other_id).or_default() += weight * other_weight;
+            }
+        }
+        drop(postings);
+
+        let mut candidates: Vec<(i64, f64)> = scores.into_iter().collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(CANDIDATES);
+        let mut scores: HashMap<i64, f64> = candidates.into_iter().collect();
+
+        // The author's latest posts are candidates even without shared words
+        let mut stmt = conn.prepare(
+            "SELECT id FROM posts WHERE user_id = ?1 AND id != ?2 AND kind != 'reblog'
+             ORDER BY created_at DESC LIMIT ?3",
+        )?;
+        let by_author = stmt.query_map(params![post.user_id, post_id, limit as i64], |row| row.get::<_, i64>(0))?;
+        for id in by_author {
+            scores.entry(id?).or_default();
+        }
+        drop(stmt);
+
+        if scores.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut values = vec![Value::Integer(viewer_id)];
+        values.extend(scores.keys().map(|id| Value::Integer(*id)));
+        let placeholders: Vec<String> = (2..=values.len()).map(|n| format!("?{}", n)).collect();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE p.id IN ({}) AND p.kind != 'reblog' AND {}",
+            POST_SELECT,
+            placeholders.join(", "),
+            VISIBLE_POSTS_FILTER
+        ))?;
+        let posts: Vec<Post> = stmt
+            .query_map(params_from_iter(values), Database::post_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        let mut related: Vec<(f64, Post)> = posts
+            .into_iter()
+            .map(|other| {
+                let mut score = scores.get(&other.id.unwrap_or(0)).copied().unwrap_or(0.0);
+                if other.user_id == post.user_id {
+                    score += SAME_AUTHOR_BOOST;
+                }
+                (score, other)
+            })
+            .collect();
+
+        related.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.created_at.cmp(&a.1.created_at)));
+        Ok(related.into_iter().take(limit).map(|(_, other)| other).collect())
+    }
+}
@@ -0,0 +1,183 @@
+// src/saved_search.rs
+
+use crate::models::SavedSearch;
+use crate::database::{POST_SELECT, VISIBLE_POSTS_FILTER, Database};
+use crate::notification::NotificationManager;
+use crate::query::Query;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use rusqlite::types::Value;
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+/// Saved searches, and the alerts they raise. Alerts are matched once per
+/// post as it is published, against that post alone, so nothing is re-run
+/// when their owners log in.
+pub struct SavedSearchManager {
+    db: Database,
+}
+
+impl SavedSearchManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    fn saved_search_from_row(row: &Row) -> rusqlite::Result<SavedSearch> {
+        Ok(SavedSearch {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            name: row.get(2)?,
+            query: row.get(3)?,
+            alerts: row.get(4)?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
+                .unwrap()
+                .with_timezone(&Utc),
+        })
+    }
+
+    /// Save `query` under `name`; the query must parse
+    pub fn save_search(&self, user_id: i64, name: &str, query: &str, alerts: bool) -> Result<SavedSearch> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::msg("Give the search a name"));
+        }
+        Query::parse(query)?;
+
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let taken: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM saved_searches WHERE user_id = ?1 AND name = ?2)",
+            params![user_id, name],
+            |row| row.get(0),
+        )?;
+        if taken {
+            return Err(Error::msg(format!("You already have a saved search called '{}'", name)));
+        }
+
+        let created_at = Utc::now();
+        conn.execute(
+            "INSERT INTO saved_searches (user_id, name, query, alerts, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![user_id, name, query.trim(), alerts, created_at.to_rfc3339()],
+        )?;
+        Ok(SavedSearch {
+            id: conn.last_insert_rowid(),
+            user_id,
+            name: name.to_string(),
+            query: query.trim().to_string(),
+            alerts,
+            created_at,
+        })
+    }
+
+    /// A user's saved searches, by name
+    pub fn get_saved_searches(&self, user_id: i64) -> Result<Vec<SavedSearch>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, name, query, alerts, created_at
+             FROM saved_searches
+             WHERE user_id = ?1
+             ORDER BY name COLLATE NOCASE ASC, id ASC"
+        )?;
+
+        let rows = stmt.query_map(params![user_id], Self::saved_search_from_row)?;
+
+        let mut result = Vec::new();
+        for saved in rows {
+            result.push(saved?);
+        }
+        Ok(result)
+    }
+
+    /// Turn alerts on or off; false if the search doesn't exist
+    pub fn set_alerts(&self, user_id: i64, search_id: i64, alerts: bool) -> Result<bool> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let updated = conn.execute(
+            "UPDATE saved_searches SET alerts = ?1 WHERE id = ?2 AND user_id = ?3",
+            params![alerts, search_id, user_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    pub fn delete_saved_search(&self, user_id: i64, search_id: i64) -> Result<bool> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let removed = conn.execute(
+            "DELETE FROM saved_searches WHERE id = ?1 AND user_id = ?2",
+            params![search_id, user_id],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Notify everyone with an alerting saved search that matches the new
+    /// post, once each however many of their searches match. Only the post
+    /// itself is searched, as each owner would see it; its author is never
+    /// alerted. Call it in the transaction that publishes the post, after
+    /// the post is written. Returns how many users were notified.
+    pub fn alert_matches(conn: &Connection, post_id: i64) -> Result<usize> {
+        let Some((author_id, title, author)): Option<(i64, String, String)> = conn
+            .query_row(
+                "SELECT p.user_id, p.title, u.username FROM posts p JOIN users u ON u.id = p.user_id WHERE p.id = ?1",
+                params![post_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?
+        else {
+            return Ok(0);
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, name, query, alerts, created_at
+             FROM saved_searches
+             WHERE alerts = 1 AND user_id != ?1
+             ORDER BY user_id, name COLLATE NOCASE ASC"
+        )?;
+        let searches: Vec<SavedSearch> = stmt
+            .query_map(params![author_id], Self::saved_search_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+
+        let mut notified = HashSet::new();
+        for saved in searches {
+            if notified.contains(&saved.user_id) {
+                continue;
+            }
+            // A query saved under older rules that no longer parses can't match
+            let Ok(query) = Query::parse(&saved.query) else {
+                continue;
+            };
+
+            // ?1 the search's owner, ?2 the post, then the query's own
+            let compiled = query.compile(3);
+            let text_match = match compiled.rank_match {
+                Some(n) => format!(" AND p.id IN (SELECT rowid FROM posts_fts WHERE posts_fts MATCH ?{})", n),
+                None => String::new(),
+            };
+            let sql = format!(
+                "SELECT EXISTS ({} WHERE p.id = ?2 AND {} AND {}{})",
+                POST_SELECT, VISIBLE_POSTS_FILTER, compiled.filter, text_match
+            );
+            let mut values = vec![Value::Integer(saved.user_id), Value::Integer(post_id)];
+            values.extend(compiled.params);
+
+            let matches: bool = conn.query_row(&sql, params_from_iter(values), |row| row.get(0))?;
+            if matches {
+                NotificationManager::notify(
+                    conn,
+                    saved.user_id,
+                    "saved_search",
+                    Some(post_id),
+                    Some(author_id),
+                    &format!("New post matching '{}': '{}' by {}", saved.name, title, author),
+                )?;
+                notified.insert(saved.user_id);
+            }
+        }
+        Ok(notified.len())
+    }
+}
@@ -0,0 +1,92 @@
+// src/search.rs
+
+use crate::models::Post;
+use crate::database::{Database, POST_SELECT, VISIBLE_POSTS_FILTER};
+use crate::query::Query;
+use crate::theme::Icon;
+use rusqlite::params_from_iter;
+use rusqlite::types::Value;
+use anyhow::Result;
+
+/// Markers placed around matched terms in snippets
+const HIGHLIGHT_START: &str = "**";
+const HIGHLIGHT_END: &str = "**";
+
+/// A post matching a search, with the passage that matched
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub post: Post,
+    pub snippet: String,
+}
+
+pub struct SearchManager {
+    db: Database,
+}
+
+impl SearchManager {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Best matches first, ranked by BM25 with title hits weighing more than
+    /// body hits; newest first when the query has no words to rank by.
+    /// Hidden posts, posts by muted or blocked users and reblogs (which only
+    /// repeat their original) are left out.
+    pub fn search(&self, viewer_id: i64, query: &Query, limit: i64) -> Result<Vec<SearchResult>> {
+        // ?1 viewer, ?2 and ?3 highlight markers, ?4 the ellipsis for cut
+        // text, ?5 limit, then the query's own
+        let compiled = query.compile(6);
+        let candidates = format!(
+            "{} WHERE p.kind != 'reblog' AND {} AND {}",
+            POST_SELECT, VISIBLE_POSTS_FILTER, compiled.filter
+        );
+        let sql = match compiled.rank_match {
+            Some(n) => format!(
+                "SELECT r.*, m.snippet
+                 FROM ({}) r
+                 JOIN (
+                     SELECT rowid, snippet(posts_fts, -1, ?2, ?3, ?4, 16) AS snippet,
+                            bm25(posts_fts, 10.0, 1.0) AS rank
+                     FROM posts_fts
+                     WHERE posts_fts MATCH ?{}
+                 ) m ON m.rowid = r.id
+                 ORDER BY m.rank, r.created_at DESC
+                 LIMIT ?5",
+                candidates, n
+            ),
+            None => format!(
+                "SELECT r.*, substr(r.content, 1, 120)
+                 FROM ({}) r
+                 ORDER BY r.created_at DESC
+                 LIMIT ?5",
+                candidates
+            ),
+        };
+
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut values: Vec<Value> = vec![
+            Value::Integer(viewer_id),
+            Value::Text(HIGHLIGHT_START.to_string()),
+            Value::Text(HIGHLIGHT_END.to_string()),
+            Value::Text(Icon::Ellipsis.to_string()),
+            Value::Integer(limit),
+        ];
+        values.extend(compiled.params);
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok(SearchResult {
+                post: Database::post_from_row(row)?,
+                snippet: row.get(14)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for search_result in rows {
+            result.push(search_result?);
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,105 @@
+// src/session.rs
+//
+// The two ends of a session: where answers to prompts are read from and
+// where everything shown is written. `CLI` only talks to a `SessionIo`, so a
+// session can be hosted on the process's own stdin and stdout, an SSH
+// channel, one stream of a multiplexed connection, or byte buffers in tests.
+
+use crate::{layout, theme};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+pub trait SessionIo: Write {
+    /// Append one line of input to `line`, newline included, as
+    /// `BufRead::read_line` does. Ok(0) means the other end closed.
+    fn read_line(&mut self, line: &mut String) -> io::Result<usize>;
+
+    /// True when both ends are a terminal this process can drive directly,
+    /// so output may be coloured and the pager and full-screen view can take
+    /// over the screen. Keys for those are read from the process's own
+    /// terminal, so only a session hosted on it should say so.
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    /// Columns and rows of the session's window, when it has one
+    fn size(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// The terminal behind the session, for full-screen views to draw on
+    /// directly. Only a session that `is_terminal` has one.
+    fn screen(&self) -> Option<Box<dyn Write>> {
+        None
+    }
+}
+
+/// Colour and line width follow `session`: escape sequences only when it is
+/// a terminal, and lines laid out for its window
+pub fn fit_output(session: &impl SessionIo) {
+    theme::set_styled(session.is_terminal());
+    layout::set_columns(session.size().map(|(columns, _)| columns));
+}
+
+/// The process's own stdin and stdout, which is the SSH session when the
+/// binary is run as a login shell
+pub struct Stdio;
+
+impl SessionIo for Stdio {
+    fn read_line(&mut self, line: &mut String) -> io::Result<usize> {
+        io::stdin().read_line(line)
+    }
+
+    fn is_terminal(&self) -> bool {
+        io::stdin().is_terminal() && io::stdout().is_terminal() && !theme::dumb_terminal()
+    }
+
+    fn size(&self) -> Option<(usize, usize)> {
+        if !io::stdout().is_terminal() {
+            return None;
+        }
+        let (columns, rows) = crossterm::terminal::size().ok()?;
+        Some((columns as usize, rows as usize))
+    }
+
+    fn screen(&self) -> Option<Box<dyn Write>> {
+        self.is_terminal().then(|| Box::new(io::stdout()) as Box<dyn Write>)
+    }
+}
+
+impl Write for Stdio {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::stdout().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+/// Any reader and writer, such as an SSH channel or in-memory buffers
+pub struct Streams<R, W> {
+    pub input: R,
+    pub output: W,
+}
+
+impl<R: BufRead, W: Write> Streams<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+}
+
+impl<R: BufRead, W: Write> SessionIo for Streams<R, W> {
+    fn read_line(&mut self, line: &mut String) -> io::Result<usize> {
+        self.input.read_line(line)
+    }
+}
+
+impl<R: BufRead, W: Write> Write for Streams<R, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
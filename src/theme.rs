@@ -0,0 +1,352 @@
+// src/theme.rs
+//
+// The presentation layer: every icon, mark and colour the interface draws
+// comes from here, so one setting changes how the whole session looks.
+// Users pick a `Theme` in their profile; NO_COLOR and TERM=dumb can still
+// narrow it, since they describe what the terminal or reader can handle.
+
+use crate::models::Theme;
+use crossterm::style::{Color, Stylize};
+use ratatui::style::{Color as TuiColor, Modifier, Style};
+use ratatui::symbols::border;
+use std::env;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// `ACTIVE` before anything has been applied
+const UNSET: u8 = u8::MAX;
+
+static ACTIVE: AtomicU8 = AtomicU8::new(UNSET);
+/// Whether the session's output is a terminal; see `session::fit_output`
+static STYLED: AtomicBool = AtomicBool::new(false);
+
+/// The theme in effect. Until a user's choice is applied it is the default,
+/// as narrowed by the environment.
+pub fn current() -> Theme {
+    match ACTIVE.load(Ordering::Relaxed) {
+        UNSET => apply(Theme::Default),
+        index => Theme::ALL[index as usize],
+    }
+}
+
+/// Switch to `chosen`, or the nearest theme the terminal can show: TERM=dumb
+/// gets plain ASCII, and NO_COLOR loses colour. Returns the theme now in
+/// effect.
+pub fn apply(chosen: Theme) -> Theme {
+    let theme = if dumb_terminal() {
+        Theme::Ascii
+    } else if no_colour() && matches!(chosen, Theme::Default | Theme::HighContrast) {
+        Theme::Monochrome
+    } else {
+        chosen
+    };
+    let index = Theme::ALL.iter().position(|t| *t == theme).unwrap_or(0);
+    ACTIVE.store(index as u8, Ordering::Relaxed);
+    theme
+}
+
+/// TERM=dumb: no cursor movement, no escape sequences, ASCII only
+pub fn dumb_terminal() -> bool {
+    env::var("TERM").is_ok_and(|term| term == "dumb")
+}
+
+/// See https://no-color.org: any non-empty NO_COLOR turns colour off
+fn no_colour() -> bool {
+    env::var("NO_COLOR").is_ok_and(|value| !value.is_empty())
+}
+
+/// Send escape sequences from now on, or stop; piped output and other
+/// sessions without a terminal stay plain text
+pub fn set_styled(styled: bool) {
+    STYLED.store(styled, Ordering::Relaxed);
+}
+
+fn styled() -> bool {
+    STYLED.load(Ordering::Relaxed)
+}
+
+/// True when only ASCII should be drawn
+pub fn ascii_only() -> bool {
+    current() == Theme::Ascii
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tone {
+    Error,
+    Success,
+    Warning,
+    Tip,
+}
+
+impl Tone {
+    fn icon(self) -> Icon {
+        match self {
+            Tone::Error => Icon::Error,
+            Tone::Success => Icon::Success,
+            Tone::Warning => Icon::Warning,
+            Tone::Tip => Icon::Tip,
+        }
+    }
+
+    /// Standard colours; high contrast uses their bright versions
+    fn colour(self, bright: bool) -> Color {
+        match (self, bright) {
+            (Tone::Error, false) => Color::DarkRed,
+            (Tone::Error, true) => Color::Red,
+            (Tone::Success, false) => Color::DarkGreen,
+            (Tone::Success, true) => Color::Green,
+            (Tone::Warning, false) => Color::DarkYellow,
+            (Tone::Warning, true) => Color::Yellow,
+            (Tone::Tip, false) => Color::DarkCyan,
+            (Tone::Tip, true) => Color::Cyan,
+        }
+    }
+}
+
+/// `text` after its tone's icon, coloured as the theme colours that tone
+fn toned(tone: Tone, text: impl fmt::Display) -> String {
+    let text = text.to_string();
+    if !styled() {
+        return format!("{} {}", tone.icon(), text);
+    }
+    let styled = match current() {
+        Theme::Default => text.with(tone.colour(false)).to_string(),
+        Theme::HighContrast => text.with(tone.colour(true)).bold().to_string(),
+        Theme::Monochrome if matches!(tone, Tone::Error | Tone::Warning) => text.bold().to_string(),
+        Theme::Monochrome | Theme::Ascii => text,
+    };
+    format!("{} {}", tone.icon(), styled)
+}
+
+/// Something went wrong
+pub fn error(text: impl fmt::Display) -> String {
+    toned(Tone::Error, text)
+}
+
+/// Something was done as asked
+pub fn success(text: impl fmt::Display) -> String {
+    toned(Tone::Success, text)
+}
+
+/// Worth stopping to read before going on
+pub fn warning(text: impl fmt::Display) -> String {
+    toned(Tone::Warning, text)
+}
+
+/// A hint about what to do next
+pub fn tip(text: impl fmt::Display) -> String {
+    toned(Tone::Tip, text)
+}
+
+/// The title of a screen or section
+pub fn heading(text: impl fmt::Display) -> String {
+    let text = text.to_string();
+    if !styled() {
+        return text;
+    }
+    match current() {
+        Theme::Default | Theme::Monochrome => text.bold().to_string(),
+        Theme::HighContrast => text.bold().underlined().to_string(),
+        Theme::Ascii => text,
+    }
+}
+
+/// Emoji and typographic marks, each with a plain-ASCII stand-in. Emoji that
+/// many terminals draw one column wide carry a trailing space so the text
+/// after them doesn't run into them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Icon {
+    Error,
+    Success,
+    Warning,
+    Tip,
+    Info,
+    New,
+    NewPosts,
+    Post,
+    Write,
+    Edit,
+    Posts,
+    Page,
+    Number,
+    Date,
+    Calendar,
+    Person,
+    AllPosts,
+    Welcome,
+    Search,
+    Find,
+    Empty,
+    Saved,
+    Notifications,
+    AlertsOn,
+    AlertsOff,
+    Deleted,
+    Top,
+    Trending,
+    Hidden,
+    Bookmark,
+    Note,
+    Comment,
+    Reblog,
+    Quote,
+    Poll,
+    Report,
+    Moderation,
+    Ranking,
+    Log,
+    MutedAndBlocked,
+    Muted,
+    Blocked,
+    Group,
+    Invite,
+    Left,
+    Export,
+    Link,
+    Key,
+    Locked,
+    Unlocked,
+    Tool,
+    Unread,
+    Bullet,
+    Separator,
+    Dash,
+    Ellipsis,
+    Times,
+    UpDown,
+    FrameTop,
+    FrameSide,
+    FrameBottom,
+}
+
+impl Icon {
+    /// The emoji or mark, then what ASCII themes show instead: a bracketed
+    /// word, so headings and messages stay told apart without the picture
+    fn glyphs(self) -> (&'static str, &'static str) {
+        match self {
+            Icon::Error => ("❌", "[error]"),
+            Icon::Success => ("✅", "[ok]"),
+            Icon::Warning => ("⚠️ ", "[warning]"),
+            Icon::Tip => ("💡", "[tip]"),
+            Icon::Info => ("ℹ️ ", "[info]"),
+            Icon::New => ("🆕", "[new]"),
+            Icon::NewPosts => ("📬", "[new]"),
+            Icon::Post => ("📝", "[post]"),
+            Icon::Write => ("✍️ ", "[write]"),
+            Icon::Edit => ("✏️ ", "[edit]"),
+            Icon::Posts => ("📚", "[posts]"),
+            Icon::Page => ("📄", "[post]"),
+            Icon::Number => ("🔢", "[id]"),
+            Icon::Date => ("📅", "[date]"),
+            Icon::Calendar => ("🗓️ ", "[archive]"),
+            Icon::Person => ("👤", "[user]"),
+            Icon::AllPosts => ("🌍", "[all]"),
+            Icon::Welcome => ("🚀", "[welcome]"),
+            Icon::Search => ("🔍", "[search]"),
+            Icon::Find => ("🔎", "[find]"),
+            Icon::Empty => ("📭", "[empty]"),
+            Icon::Saved => ("💾", "[saved]"),
+            Icon::Notifications => ("🔔", "[notifications]"),
+            Icon::AlertsOn => ("🔔", "[on]"),
+            Icon::AlertsOff => ("🔕", "[off]"),
+            Icon::Deleted => ("🗑️ ", "[deleted]"),
+            Icon::Top => ("🏆", "[top]"),
+            Icon::Trending => ("🔥", "[trending]"),
+            Icon::Hidden => ("🙈", "[hidden]"),
+            Icon::Bookmark => ("🔖", "[bookmark]"),
+            Icon::Note => ("🗒️ ", "[note]"),
+            Icon::Comment => ("💬", "[comment]"),
+            Icon::Reblog => ("🔁", "[reblog]"),
+            Icon::Quote => ("❝", "[quote]"),
+            Icon::Poll => ("📊", "[poll]"),
+            Icon::Report => ("🚩", "[report]"),
+            Icon::Moderation => ("🛡️ ", "[moderation]"),
+            Icon::Ranking => ("📈", "[ranking]"),
+            Icon::Log => ("📜", "[log]"),
+            Icon::MutedAndBlocked => ("🚫", "[blocked]"),
+            Icon::Muted => ("🔇", "[muted]"),
+            Icon::Blocked => ("⛔", "[blocked]"),
+            Icon::Group => ("👥", "[group]"),
+            Icon::Invite => ("📨", "[invite]"),
+            Icon::Left => ("👋", "[left]"),
+            Icon::Export => ("📦", "[export]"),
+            Icon::Link => ("🔗", "[link]"),
+            Icon::Key => ("🔑", "[key]"),
+            Icon::Locked => ("🔐", "[locked]"),
+            Icon::Unlocked => ("🔓", "[dev]"),
+            Icon::Tool => ("🔧", "[dev]"),
+            Icon::Unread => ("●", "*"),
+            Icon::Bullet => ("•", "-"),
+            Icon::Separator => ("·", "|"),
+            Icon::Dash => ("—", "-"),
+            Icon::Ellipsis => ("…", "..."),
+            Icon::Times => ("×", "x"),
+            Icon::UpDown => ("↑↓", "j/k"),
+            Icon::FrameTop => ("┌─", "+-"),
+            Icon::FrameSide => ("│", "|"),
+            Icon::FrameBottom => ("└─", "+-"),
+        }
+    }
+}
+
+impl fmt::Display for Icon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (glyph, ascii) = self.glyphs();
+        f.write_str(if ascii_only() { ascii } else { glyph })
+    }
+}
+
+/// `ch` if the theme can draw it, otherwise '-' for a rule
+pub fn rule_char(ch: char) -> char {
+    if ascii_only() && !ch.is_ascii() { '-' } else { ch }
+}
+
+// Full-screen styles
+
+/// Border and title of the focused pane
+pub fn focused() -> Style {
+    match current() {
+        Theme::Default => Style::default().fg(TuiColor::Cyan).add_modifier(Modifier::BOLD),
+        Theme::HighContrast => Style::default().fg(TuiColor::LightYellow).add_modifier(Modifier::BOLD),
+        Theme::Monochrome | Theme::Ascii => Style::default().add_modifier(Modifier::BOLD),
+    }
+}
+
+/// Secondary text: bylines, dates, hints. High contrast never dims.
+pub fn secondary() -> Style {
+    match current() {
+        Theme::HighContrast => Style::default(),
+        _ => Style::default().add_modifier(Modifier::DIM),
+    }
+}
+
+/// The selected list row, and the status bar
+pub fn highlighted() -> Style {
+    match current() {
+        Theme::HighContrast => Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+        _ => Style::default().add_modifier(Modifier::REVERSED),
+    }
+}
+
+/// Post titles in lists
+pub fn title() -> Style {
+    Style::default().add_modifier(Modifier::BOLD)
+}
+
+/// Pane borders: box drawing, or +, - and | for ASCII
+pub fn borders() -> border::Set {
+    if ascii_only() {
+        border::Set {
+            top_left: "+",
+            top_right: "+",
+            bottom_left: "+",
+            bottom_right: "+",
+            vertical_left: "|",
+            vertical_right: "|",
+            horizontal_top: "-",
+            horizontal_bottom: "-",
+        }
+    } else {
+        border::PLAIN
+    }
+}
@@ -0,0 +1,792 @@
+// src/tui.rs
+//
+// The full-screen interface, used when both ends of the session are a
+// terminal: a sidebar of sections, the posts in the chosen section beside
+// the selected post, and a status bar. Writing, comments and everything else
+// it doesn't do itself run in the numbered menu with the screen suspended,
+// and the screen comes back when they finish.
+
+use crate::models::{author_label, Cursor, Notification, PageRequest, Post, PostKind, User};
+use crate::database::Database;
+use crate::post::PostManager;
+use crate::activity::ActivityManager;
+use crate::search::SearchManager;
+use crate::notification::NotificationManager;
+use crate::ranking::RankingManager;
+use crate::moderation::ModerationManager;
+use crate::query::Query;
+use crate::cli::CLI;
+use crate::pager::RawScreen;
+use crate::session::SessionIo;
+use crate::theme::{self, Icon};
+use anyhow::Result;
+use chrono::Utc;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+use std::collections::HashSet;
+use std::io;
+use std::time::Duration;
+
+/// Posts fetched at a time; more are loaded on reaching the end of the list
+const PAGE_SIZE: usize = 50;
+/// How long to wait for a key before looking for live updates again
+const TICK: Duration = Duration::from_millis(250);
+/// Narrower than this, the reading pane goes under the list instead of beside it
+const SIDE_BY_SIDE_WIDTH: u16 = 100;
+const SIDEBAR_WIDTH: u16 = 22;
+
+fn help() -> String {
+    [
+        "Tab switch pane",
+        &format!("{} move", Icon::UpDown),
+        "Enter open",
+        "/ search",
+        "w write",
+        "m menu",
+        "r refresh",
+        "q quit",
+    ]
+    .join(&format!(" {} ", Icon::Separator))
+}
+
+/// True when the session can show the full-screen interface
+pub fn is_available(session: &impl SessionIo) -> bool {
+    session.is_terminal()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Home,
+    All,
+    Mine,
+    Search,
+    Notifications,
+}
+
+impl Section {
+    const ALL: [Section; 5] = [Section::Home, Section::All, Section::Mine, Section::Search, Section::Notifications];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Section::Home => "Home",
+            Section::All => "All posts",
+            Section::Mine => "My posts",
+            Section::Search => "Search",
+            Section::Notifications => "Notifications",
+        }
+    }
+
+    /// Shown in the list pane when there is nothing to list
+    fn empty_hint(&self) -> &'static str {
+        match self {
+            Section::Home => "You're all caught up.",
+            Section::All => "Nothing has been posted yet.",
+            Section::Mine => "You haven't posted yet. Press w to write something.",
+            Section::Search => "Press / to search.",
+            Section::Notifications => "No notifications yet.",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Sidebar,
+    List,
+    Reader,
+}
+
+enum Entry {
+    Post(Post),
+    Notification(Notification),
+}
+
+impl Entry {
+    fn post_id(&self) -> Option<i64> {
+        match self {
+            Entry::Post(post) => post.id,
+            Entry::Notification(notification) => notification.post_id,
+        }
+    }
+}
+
+/// What the reading pane shows
+struct Reading {
+    title: String,
+    text: Text<'static>,
+}
+
+/// Something that runs with the screen suspended
+enum Task {
+    Write,
+    Open(i64),
+    Menu,
+}
+
+enum Action {
+    Stay,
+    Suspend(Task),
+    Quit,
+}
+
+pub struct Tui {
+    user: User,
+    post_manager: PostManager,
+    activity_manager: ActivityManager,
+    search_manager: SearchManager,
+    notification_manager: NotificationManager,
+    ranking_manager: RankingManager,
+    moderation_manager: ModerationManager,
+    section: Section,
+    focus: Focus,
+    entries: Vec<Entry>,
+    more: Option<Cursor>, // Where the next page of the section starts
+    list: ListState,
+    unread: HashSet<i64>,
+    unread_notifications: i64,
+    reading: Option<Reading>,
+    scroll: usize,
+    reader_rows: usize, // Text rows in the reading pane at the last draw
+    query: Option<String>,
+    input: Option<String>, // The search being typed, while the status bar asks for one
+    message: Option<String>,
+    banner: Option<String>,
+}
+
+impl Tui {
+    pub fn new(db: Database, user: User) -> Self {
+        Self {
+            user,
+            post_manager: PostManager::new(db.clone()),
+            activity_manager: ActivityManager::new(db.clone()),
+            search_manager: SearchManager::new(db.clone()),
+            notification_manager: NotificationManager::new(db.clone()),
+            ranking_manager: RankingManager::new(db.clone()),
+            moderation_manager: ModerationManager::new(db),
+            section: Section::Home,
+            focus: Focus::List,
+            entries: Vec::new(),
+            more: None,
+            list: ListState::default(),
+            unread: HashSet::new(),
+            unread_notifications: 0,
+            reading: None,
+            scroll: 0,
+            reader_rows: 0,
+            query: None,
+            input: None,
+            message: None,
+            banner: None,
+        }
+    }
+
+    /// Run until the user quits. `cli` runs whatever the screen hands off.
+    pub fn run<S: SessionIo>(mut self, cli: &mut CLI<S>) -> io::Result<()> {
+        self.load();
+        self.show_warnings();
+
+        let mut screen = Some(RawScreen::enter(cli.session())?);
+        let out = cli
+            .session()
+            .screen()
+            .ok_or_else(|| io::Error::other("This session has no terminal to take over"))?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(out))?;
+        terminal.clear()?;
+
+        loop {
+            if let Some(updates) = cli.take_live_updates() {
+                self.banner = Some(format!("{} {} {} r to refresh", Icon::Notifications, updates.banner(), Icon::Separator));
+                if updates.notifications > 0 {
+                    self.count_notifications();
+                }
+                if updates.warnings > 0 {
+                    self.show_warnings();
+                }
+            }
+
+            // Resizes need nothing else: each draw lays out for the current size
+            terminal.draw(|frame| self.draw(frame))?;
+            if !event::poll(TICK)? {
+                continue;
+            }
+            let key = match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => key,
+                _ => continue,
+            };
+
+            let task = match self.handle_key(key) {
+                Action::Stay => continue,
+                Action::Quit => return Ok(()),
+                Action::Suspend(task) => task,
+            };
+
+            drop(screen.take());
+            let keep_going = match task {
+                Task::Write => {
+                    cli.create_post(&self.user)?;
+                    true
+                }
+                Task::Open(post_id) => {
+                    cli.open_post(&self.user, post_id)?;
+                    true
+                }
+                Task::Menu => cli.run_menu_once(&mut self.user)?,
+            };
+            if !keep_going {
+                return Ok(());
+            }
+            screen = Some(RawScreen::enter(cli.session())?);
+            terminal.clear()?;
+            self.reload();
+        }
+    }
+
+    fn user_id(&self) -> i64 {
+        self.user.id.unwrap()
+    }
+
+    /// One page of the current section
+    fn fetch(&self, request: PageRequest) -> Result<(Vec<Entry>, Option<Cursor>)> {
+        let user_id = self.user_id();
+        let page = match self.section {
+            Section::Home => self.activity_manager.get_unread_posts(user_id, request, PAGE_SIZE)?,
+            Section::All => self.post_manager.get_all_posts(user_id, request, PAGE_SIZE)?,
+            Section::Mine => self.post_manager.get_user_posts(user_id, request, PAGE_SIZE)?,
+            // Search results and notifications come in one batch, best or newest first
+            Section::Search => {
+                let Some(query) = self.query.as_deref().filter(|_| request == PageRequest::First) else {
+                    return Ok((Vec::new(), None));
+                };
+                let results = self.search_manager.search(user_id, &Query::parse(query)?, PAGE_SIZE as i64)?;
+                return Ok((results.into_iter().map(|result| Entry::Post(result.post)).collect(), None));
+            }
+            Section::Notifications => {
+                if request != PageRequest::First {
+                    return Ok((Vec::new(), None));
+                }
+                let notifications = self.notification_manager.get_notifications(user_id, PAGE_SIZE as i64)?;
+                return Ok((notifications.into_iter().map(Entry::Notification).collect(), None));
+            }
+        };
+        Ok((page.items.into_iter().map(Entry::Post).collect(), page.next))
+    }
+
+    /// Fetch the section afresh and preview its first entry
+    fn load(&mut self) {
+        self.count_notifications();
+        self.unread = self.activity_manager.get_unread_post_ids(self.user_id()).unwrap_or_default();
+        match self.fetch(PageRequest::First) {
+            Ok((entries, more)) => {
+                self.entries = entries;
+                self.more = more;
+            }
+            Err(e) => {
+                self.entries.clear();
+                self.more = None;
+                self.message = Some(format!("Error loading {}: {}", self.section.title(), e));
+            }
+        }
+        self.list.select(None);
+        self.reading = None;
+        if !self.entries.is_empty() {
+            self.preview(0);
+        }
+    }
+
+    /// Load again after the screen was suspended or on request, keeping the
+    /// place in the list where possible
+    fn reload(&mut self) {
+        let selected = self.list.selected();
+        self.banner = None;
+        self.load();
+        if let Some(index) = selected
+            && !self.entries.is_empty()
+        {
+            self.preview(index.min(self.entries.len() - 1));
+        }
+    }
+
+    fn load_more(&mut self) {
+        let Some(cursor) = self.more else {
+            return;
+        };
+        match self.fetch(PageRequest::After(cursor)) {
+            Ok((entries, more)) => {
+                self.entries.extend(entries);
+                self.more = more;
+            }
+            Err(e) => self.message = Some(format!("Error loading more posts: {}", e)),
+        }
+    }
+
+    fn count_notifications(&mut self) {
+        self.unread_notifications = self.notification_manager.count_unread(self.user_id()).unwrap_or(0);
+    }
+
+    fn switch_section(&mut self, section: Section) {
+        if self.section != section {
+            self.section = section;
+            self.load();
+        }
+    }
+
+    /// Select an entry and show it in the reading pane, without marking it read
+    fn preview(&mut self, index: usize) {
+        if index + 1 >= self.entries.len() {
+            self.load_more();
+        }
+        let index = index.min(self.entries.len().saturating_sub(1));
+        let Some(entry) = self.entries.get(index) else {
+            return;
+        };
+        self.list.select(Some(index));
+        self.scroll = 0;
+        self.reading = Some(match entry {
+            Entry::Post(post) => self.post_reading(post),
+            Entry::Notification(notification) => match notification.post_id {
+                Some(post_id) => match self.post_manager.get_post(post_id) {
+                    Ok(Some(post)) => self.post_reading(&post),
+                    _ => message_reading("Notification", &notification.message),
+                },
+                None => message_reading("Notification", &notification.message),
+            },
+        });
+    }
+
+    /// Select an entry as the user moves to it: it is shown and counts as read
+    fn select(&mut self, index: usize) {
+        self.preview(index);
+        self.mark_read();
+    }
+
+    fn mark_read(&mut self) {
+        let user_id = self.user_id();
+        let Some(entry) = self.list.selected().and_then(|index| self.entries.get_mut(index)) else {
+            return;
+        };
+        if let Entry::Notification(notification) = entry
+            && notification.read_at.is_none()
+            && self.notification_manager.mark_read(user_id, notification.id).is_ok()
+        {
+            notification.read_at = Some(Utc::now());
+            self.unread_notifications = (self.unread_notifications - 1).max(0);
+        }
+        if let Some(post_id) = entry.post_id()
+            && self.unread.remove(&post_id)
+        {
+            let _ = self.activity_manager.mark_read(user_id, post_id);
+            let _ = self.ranking_manager.record_view(user_id, post_id);
+        }
+    }
+
+    /// The post a reblog or quote points at, unless it was deleted or hidden
+    fn load_original(&self, post: &Post) -> Option<Post> {
+        let original_id = post.original_post_id?;
+        self.post_manager
+            .get_post(original_id)
+            .ok()
+            .flatten()
+            .filter(|original| original.hidden_at.is_none())
+    }
+
+    fn post_reading(&self, post: &Post) -> Reading {
+        let dim = theme::secondary();
+        let mut lines = Vec::new();
+
+        let shown = if post.kind == PostKind::Reblog {
+            lines.push(Line::styled(
+                format!("{} {} reblogged", Icon::Reblog, post.author_username.as_deref().unwrap_or("unknown")),
+                dim,
+            ));
+            match self.load_original(post) {
+                Some(original) => original,
+                None => {
+                    lines.push(Line::raw(format!("{} The original post is unavailable", Icon::Warning)));
+                    return Reading { title: post.title.clone(), text: Text::from(lines) };
+                }
+            }
+        } else {
+            post.clone()
+        };
+
+        if shown.hidden_at.is_some() && shown.user_id != self.user_id() && !self.user.is_admin {
+            return message_reading(
+                &shown.title,
+                &format!("{} This post has been hidden by the moderators", Icon::Hidden),
+            );
+        }
+
+        let author = author_label(
+            shown.author_username.as_deref().unwrap_or("unknown"),
+            shown.author_display_name.as_deref(),
+        );
+        let mut byline = format!("by {}", author);
+        if let Some(pronouns) = &shown.author_pronouns {
+            byline.push_str(&format!(" {} {}", Icon::Separator, pronouns));
+        }
+        byline.push_str(&format!(" {} {}", Icon::Separator, shown.created_at.format("%Y-%m-%d %H:%M UTC")));
+        if let Some(slug) = &shown.group_slug {
+            byline.push_str(&format!(" {} in #{}", Icon::Separator, slug));
+        }
+        lines.push(Line::styled(byline, dim));
+        if shown.hidden_at.is_some() {
+            lines.push(Line::raw(format!("{} Hidden by the moderators", Icon::Hidden)));
+        }
+        lines.push(Line::default());
+        lines.extend(shown.content.lines().map(|line| Line::raw(line.to_string())));
+
+        if shown.kind == PostKind::Quote {
+            let quoting = match self.load_original(&shown) {
+                Some(quoted) => format!(
+                    "{} Quoting '{}' by {}",
+                    Icon::Quote,
+                    quoted.title,
+                    quoted.author_username.as_deref().unwrap_or("unknown")
+                ),
+                None => format!("{} Quoting: original unavailable", Icon::Quote),
+            };
+            lines.push(Line::default());
+            lines.push(Line::styled(quoting, dim));
+        }
+        lines.push(Line::default());
+        lines.push(Line::styled("Enter for comments, polls and actions", dim));
+
+        Reading { title: shown.title.clone(), text: Text::from(lines) }
+    }
+
+    fn show_warnings(&mut self) {
+        let Ok(warnings) = self.moderation_manager.take_unseen_warnings(self.user_id()) else {
+            return;
+        };
+        if warnings.is_empty() {
+            return;
+        }
+        let mut lines = Vec::new();
+        for warning in warnings {
+            lines.push(Line::styled(
+                warning.created_at.format("%Y-%m-%d").to_string(),
+                theme::secondary(),
+            ));
+            lines.push(Line::raw(warning.message));
+            lines.push(Line::default());
+        }
+        self.reading = Some(Reading {
+            title: format!("{} Warning from the moderators", Icon::Warning),
+            text: Text::from(lines),
+        });
+        self.scroll = 0;
+        self.focus = Focus::Reader;
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Action::Quit;
+        }
+        if self.input.is_some() {
+            self.search_key(key);
+            return Action::Stay;
+        }
+        self.message = None;
+
+        match key.code {
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Char('w') => return Action::Suspend(Task::Write),
+            KeyCode::Char('m') => return Action::Suspend(Task::Menu),
+            KeyCode::Char('r') => self.reload(),
+            KeyCode::Char('?') => self.message = Some(help()),
+            KeyCode::Char('/') => {
+                self.switch_section(Section::Search);
+                self.input = Some(self.query.clone().unwrap_or_default());
+            }
+            KeyCode::Char(c @ '1'..='5') => {
+                let section = Section::ALL[c as usize - '1' as usize];
+                self.switch_section(section);
+            }
+            KeyCode::Tab => self.set_focus(match self.focus {
+                Focus::Sidebar => Focus::List,
+                Focus::List => Focus::Reader,
+                Focus::Reader => Focus::Sidebar,
+            }),
+            KeyCode::BackTab => self.set_focus(match self.focus {
+                Focus::Sidebar => Focus::Reader,
+                Focus::List => Focus::Sidebar,
+                Focus::Reader => Focus::List,
+            }),
+            _ => {
+                return match self.focus {
+                    Focus::Sidebar => self.sidebar_key(key),
+                    Focus::List => self.list_key(key),
+                    Focus::Reader => self.reader_key(key),
+                };
+            }
+        }
+        Action::Stay
+    }
+
+    fn set_focus(&mut self, focus: Focus) {
+        self.focus = focus;
+        // Moving into a post counts as reading it
+        if focus != Focus::Sidebar {
+            self.mark_read();
+        }
+    }
+
+    fn sidebar_key(&mut self, key: KeyEvent) -> Action {
+        let index = Section::ALL.iter().position(|s| *s == self.section).unwrap_or(0);
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.switch_section(Section::ALL[index.saturating_sub(1)]),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.switch_section(Section::ALL[(index + 1).min(Section::ALL.len() - 1)])
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                if self.section == Section::Search && self.query.is_none() {
+                    self.input = Some(String::new());
+                } else {
+                    self.set_focus(Focus::List);
+                }
+            }
+            _ => self.message = Some(help()),
+        }
+        Action::Stay
+    }
+
+    fn list_key(&mut self, key: KeyEvent) -> Action {
+        let selected = self.list.selected().unwrap_or(0);
+        let page = self.reader_rows.max(1);
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.select(selected.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.select(selected + 1),
+            KeyCode::PageUp => self.select(selected.saturating_sub(page)),
+            KeyCode::PageDown => self.select(selected + page),
+            KeyCode::Home | KeyCode::Char('g') => self.select(0),
+            KeyCode::End | KeyCode::Char('G') => self.select(self.entries.len().saturating_sub(1)),
+            KeyCode::Left | KeyCode::Char('h') => self.set_focus(Focus::Sidebar),
+            KeyCode::Right | KeyCode::Char('l') => self.set_focus(Focus::Reader),
+            KeyCode::Enter | KeyCode::Char('o') => return self.open_selected(),
+            _ => self.message = Some(help()),
+        }
+        Action::Stay
+    }
+
+    fn reader_key(&mut self, key: KeyEvent) -> Action {
+        let page = self.reader_rows.max(1);
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll += 1,
+            KeyCode::PageUp | KeyCode::Char('b') => self.scroll = self.scroll.saturating_sub(page),
+            KeyCode::PageDown | KeyCode::Char(' ') | KeyCode::Char('f') => self.scroll += page,
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            // Clamped to the end of the text when drawn
+            KeyCode::End | KeyCode::Char('G') => self.scroll = usize::MAX,
+            KeyCode::Left | KeyCode::Char('h') => self.set_focus(Focus::List),
+            KeyCode::Enter | KeyCode::Char('o') => return self.open_selected(),
+            _ => self.message = Some(help()),
+        }
+        Action::Stay
+    }
+
+    fn open_selected(&mut self) -> Action {
+        match self.list.selected().and_then(|index| self.entries.get(index)).and_then(Entry::post_id) {
+            Some(post_id) => {
+                self.mark_read();
+                Action::Suspend(Task::Open(post_id))
+            }
+            None => Action::Stay,
+        }
+    }
+
+    fn search_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.input = None;
+                self.message = None;
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Enter => {
+                let text = input.trim().to_string();
+                if text.is_empty() {
+                    self.input = None;
+                    return;
+                }
+                match Query::parse(&text) {
+                    Ok(_) => {
+                        self.input = None;
+                        self.message = None;
+                        self.query = Some(text);
+                        self.load();
+                        self.set_focus(Focus::List);
+                    }
+                    // Keep the search open so it can be fixed
+                    Err(e) => self.message = Some(format!("{} (at column {})", e, e.position + 1)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [sidebar, rest] =
+            Layout::horizontal([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(1)]).areas(main);
+        let [list, reader] = if rest.width >= SIDE_BY_SIDE_WIDTH {
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(rest)
+        } else {
+            Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(rest)
+        };
+
+        self.draw_sidebar(frame, sidebar);
+        self.draw_list(frame, list);
+        self.draw_reader(frame, reader);
+        self.draw_status(frame, status);
+    }
+
+    fn pane(&self, title: String, focus: Focus) -> Block<'static> {
+        let style = if self.focus == focus { theme::focused() } else { Style::default() };
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(theme::borders())
+            .border_style(style)
+            .title(title)
+    }
+
+    fn draw_sidebar(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = Section::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, section)| {
+                let label = match section {
+                    Section::Notifications if self.unread_notifications > 0 => {
+                        format!("{} {} ({})", index + 1, section.title(), self.unread_notifications)
+                    }
+                    _ => format!("{} {}", index + 1, section.title()),
+                };
+                ListItem::new(label)
+            })
+            .collect();
+        let mut state = ListState::default();
+        state.select(Section::ALL.iter().position(|s| *s == self.section));
+
+        let list = List::new(items)
+            .block(self.pane("SSH Blog".to_string(), Focus::Sidebar))
+            .highlight_style(theme::highlighted());
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let title = match (&self.section, &self.query) {
+            (Section::Search, Some(query)) => format!("Search: {}", query),
+            _ => self.section.title().to_string(),
+        };
+        let block = self.pane(title, Focus::List);
+
+        if self.entries.is_empty() {
+            let hint = Paragraph::new(self.section.empty_hint()).wrap(Wrap { trim: true }).block(block);
+            frame.render_widget(hint, area);
+            return;
+        }
+
+        let dim = theme::secondary();
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Post(post) => {
+                    let unread = post.id.is_some_and(|id| self.unread.contains(&id));
+                    let marker = match post.kind {
+                        PostKind::Reblog => format!("{} ", Icon::Reblog),
+                        _ if unread => format!("{} ", Icon::Unread),
+                        _ => "  ".to_string(),
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::raw(marker),
+                        Span::styled(post.title.clone(), theme::title()),
+                        Span::styled(
+                            format!(
+                                " {} {} {} {}",
+                                Icon::Dash,
+                                post.author_username.as_deref().unwrap_or("unknown"),
+                                Icon::Separator,
+                                post.created_at.format("%Y-%m-%d")
+                            ),
+                            dim,
+                        ),
+                    ]))
+                }
+                Entry::Notification(notification) => ListItem::new(Line::from(vec![
+                    Span::raw(if notification.read_at.is_none() { format!("{} ", Icon::Unread) } else { "  ".to_string() }),
+                    Span::raw(notification.message.clone()),
+                    Span::styled(
+                        format!(" {} {}", Icon::Separator, notification.created_at.format("%Y-%m-%d %H:%M")),
+                        dim,
+                    ),
+                ])),
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme::highlighted());
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_reader(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(reading) = &self.reading else {
+            frame.render_widget(self.pane(String::new(), Focus::Reader), area);
+            self.reader_rows = 0;
+            return;
+        };
+
+        let block = self.pane(reading.title.clone(), Focus::Reader);
+        let inner = block.inner(area);
+        let paragraph = Paragraph::new(reading.text.clone()).wrap(Wrap { trim: false });
+        let rows = paragraph.line_count(inner.width);
+        self.reader_rows = inner.height as usize;
+        self.scroll = self.scroll.min(rows.saturating_sub(self.reader_rows));
+
+        let title = if rows > self.reader_rows {
+            let bottom = (self.scroll + self.reader_rows).min(rows);
+            format!("{} ({}%)", reading.title, bottom * 100 / rows.max(1))
+        } else {
+            reading.title.clone()
+        };
+        let paragraph = paragraph
+            .block(self.pane(title, Focus::Reader))
+            .scroll((self.scroll.min(u16::MAX as usize) as u16, 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let style = theme::highlighted();
+        let banner = self.banner.clone().unwrap_or_default();
+        let banner_width = Line::raw(banner.as_str()).width().min(area.width as usize / 2) as u16;
+        let [left, right] = Layout::horizontal([Constraint::Min(1), Constraint::Length(banner_width)]).areas(area);
+
+        let text = match (&self.input, &self.message) {
+            (Some(input), Some(message)) => format!("Search: {}  {} {}", input, Icon::Warning, message),
+            (Some(input), None) => format!("Search: {}", input),
+            (None, Some(message)) => message.clone(),
+            (None, None) => help(),
+        };
+        frame.render_widget(Paragraph::new(text).style(style), left);
+        frame.render_widget(Paragraph::new(banner).style(style.add_modifier(Modifier::BOLD)), right);
+
+        if let Some(input) = &self.input {
+            let x = left.x + Line::raw(format!("Search: {}", input)).width() as u16;
+            frame.set_cursor_position((x.min(left.right().saturating_sub(1)), left.y));
+        }
+    }
+}
+
+fn message_reading(title: &str, message: &str) -> Reading {
+    Reading { title: title.to_string(), text: Text::from(message.to_string()) }
+}
@@ -1,10 +1,13 @@
 // src/user.rs
 
-use crate::models::User;
-use crate::database::Database;
-use rusqlite::params;
+use crate::models::{User, UserSummary};
+use crate::database::{Database, USER_SELECT};
+use crate::session::SessionIo;
+use crate::theme::{self, Icon};
+use rusqlite::{params, Connection};
 use anyhow::{Error, Result};
-use std::io::{self, Write};
+use chrono::Utc;
+use chrono_tz::Tz;
 use std::env;
 use std::fs;
 use std::process::Command;
@@ -18,58 +21,73 @@ impl UserManager {
         Self { db }
     }
 
+    /// The rules `register.sh` enforces: at least 3 characters from `[a-zA-Z0-9_-]`
+    pub fn validate_username(username: &str) -> Result<()> {
+        if username.len() < 3 {
+            return Err(Error::msg("Username must be at least 3 characters long"));
+        }
+        if !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(Error::msg(
+                "Username can only contain letters, numbers, underscores, and hyphens",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether `name` is in use by anyone other than `except_user_id`, as a
+    /// current username, a former one or a login name. Former names stay
+    /// reserved so old links never start pointing at someone else.
+    fn name_taken(conn: &Connection, name: &str, except_user_id: Option<i64>) -> Result<bool> {
+        let taken = conn.query_row(
+            "SELECT EXISTS (
+                 SELECT 1 FROM users WHERE (username = ?1 OR login_name = ?1) AND id IS NOT ?2
+                 UNION ALL
+                 SELECT 1 FROM username_aliases WHERE alias = ?1 AND user_id IS NOT ?2
+             )",
+            params![name, except_user_id],
+            |row| row.get(0),
+        )?;
+        Ok(taken)
+    }
+
     pub fn create_user_direct(&self, user: &mut User) -> Result<()> {
+        Self::validate_username(&user.username)?;
+
         let conn_arc = self.db.get_connection();
         let conn = conn_arc.lock().unwrap();
 
+        if Self::name_taken(&conn, &user.username, None)? {
+            return Err(Error::msg(format!("Username '{}' is already taken", user.username)));
+        }
+
         let mut stmt = conn.prepare(
-            "INSERT INTO users (username, ssh_key, bio, created_at) VALUES (?1, ?2, ?3, ?4)"
+            "INSERT INTO users (username, ssh_key, bio, created_at, login_name) VALUES (?1, ?2, ?3, ?4, ?5)"
         )?;
 
         let id = stmt.insert(params![
             user.username,
             user.ssh_key,
             user.bio,
-            user.created_at.to_rfc3339()
+            user.created_at.to_rfc3339(),
+            user.login_name
         ])?;
 
         user.id = Some(id);
         Ok(())
     }
 
-    pub fn register_user_with_ssh(&self) -> Result<User> {
-        println!("Enter your desired username:");
-        print!("> ");
-        io::stdout().flush()?;
-        
-        let mut username = String::new();
-        io::stdin().read_line(&mut username)?;
-        let username = username.trim().to_string();
+    pub fn register_user_with_ssh(&self, session: &mut impl SessionIo) -> Result<User> {
+        let username = Self::ask(session, "Enter your desired username:")?;
+        Self::validate_username(&username)?;
 
-        if username.len() < 3 {
-            return Err(Error::msg("Username must be at least 3 characters long"));
-        }
-
-        println!("Enter your SSH public key:");
-        print!("> ");
-        io::stdout().flush()?;
-        
-        let mut ssh_key = String::new();
-        io::stdin().read_line(&mut ssh_key)?;
-        let ssh_key = ssh_key.trim().to_string();
+        let ssh_key = Self::ask(session, "Enter your SSH public key:")?;
 
         if !ssh_key.starts_with("ssh-") {
             return Err(Error::msg("Invalid SSH key format"));
         }
 
-        println!("Enter your bio (optional, press Enter to skip):");
-        print!("> ");
-        io::stdout().flush()?;
-        
-        let mut bio = String::new();
-        io::stdin().read_line(&mut bio)?;
-        let bio = bio.trim();
-        let bio = if bio.is_empty() { None } else { Some(bio.to_string()) };
+        let bio = Self::ask(session, "Enter your bio (optional, press Enter to skip):")?;
+        let bio = if bio.is_empty() { None } else { Some(bio) };
 
         let mut user = User::new(username, ssh_key, bio);
         self.create_user_direct(&mut user)?;
@@ -77,55 +95,260 @@ impl UserManager {
         Ok(user)
     }
 
+    /// Ask `question` on its own line and read the answer after a "> " prompt
+    fn ask(session: &mut impl SessionIo, question: &str) -> Result<String> {
+        writeln!(session, "{}", question)?;
+        write!(session, "> ")?;
+        session.flush()?;
+
+        let mut answer = String::new();
+        session.read_line(&mut answer)?;
+        Ok(answer.trim().to_string())
+    }
+
+    /// Look a user up by their current username or, failing that, a former one
     pub fn find_by_username(&self, username: &str) -> Result<Option<User>> {
         let conn_arc = self.db.get_connection();
         let conn = conn_arc.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, username, ssh_key, bio, created_at 
-             FROM users 
-             WHERE username = ?1"
-        )?;
+        let username = username.trim_start_matches('@');
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE username = ?1
+             OR id = (SELECT user_id FROM username_aliases WHERE alias = ?1)
+             ORDER BY username = ?1 DESC
+             LIMIT 1",
+            USER_SELECT
+        ))?;
 
         let mut rows = stmt.query(params![username])?;
         if let Some(row) = rows.next()? {
-            let user = User {
-                id: Some(row.get(0)?),
-                username: row.get(1)?,
-                ssh_key: row.get(2)?,
-                bio: row.get(3)?,
-                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
-                    .unwrap()
-                    .with_timezone(&chrono::Utc),
-            };
-            Ok(Some(user))
+            Ok(Some(Database::user_from_row(row)?))
         } else {
             Ok(None)
         }
     }
 
-    pub fn find_by_ssh_key(&self, ssh_key: &str) -> Result<Option<User>> {
+    /// Look a user up by the system account they log in with
+    pub fn find_by_login_name(&self, login_name: &str) -> Result<Option<User>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!("{} WHERE login_name = ?1", USER_SELECT))?;
+
+        let mut rows = stmt.query(params![login_name])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(Database::user_from_row(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Change the blog username, keeping the old one as an alias. The login
+    /// name is left alone, so SSH access keeps working unchanged.
+    pub fn rename_user(&self, user: &mut User, new_username: &str) -> Result<()> {
+        Self::validate_username(new_username)?;
+        if new_username == user.username {
+            return Err(Error::msg("That is already your username"));
+        }
+        let user_id = user.id.ok_or_else(|| Error::msg("User has not been saved"))?;
+
+        let conn_arc = self.db.get_connection();
+        let mut conn = conn_arc.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        if Self::name_taken(&tx, new_username, Some(user_id))? {
+            return Err(Error::msg(format!("Username '{}' is already taken", new_username)));
+        }
+
+        // Taking back one of your own former names retires that alias
+        tx.execute(
+            "DELETE FROM username_aliases WHERE alias = ?1 AND user_id = ?2",
+            params![new_username, user_id],
+        )?;
+        tx.execute(
+            "INSERT INTO username_aliases (alias, user_id, created_at) VALUES (?1, ?2, ?3)",
+            params![user.username, user_id, Utc::now().to_rfc3339()],
+        )?;
+        tx.execute(
+            "UPDATE users SET username = ?1 WHERE id = ?2",
+            params![new_username, user_id],
+        )?;
+
+        tx.commit()?;
+        user.username = new_username.to_string();
+        Ok(())
+    }
+
+    /// Former usernames, most recent first
+    pub fn get_aliases(&self, user_id: i64) -> Result<Vec<String>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT alias FROM username_aliases WHERE user_id = ?1 ORDER BY created_at DESC"
+        )?;
+        let rows = stmt.query_map(params![user_id], |row| row.get(0))?;
+
+        let mut result = Vec::new();
+        for alias in rows {
+            result.push(alias?);
+        }
+        Ok(result)
+    }
+
+    /// All users, alphabetically, optionally narrowed to names containing `search`
+    pub fn list_users(&self, search: Option<&str>) -> Result<Vec<UserSummary>> {
         let conn_arc = self.db.get_connection();
         let conn = conn_arc.lock().unwrap();
 
         let mut stmt = conn.prepare(
-            "SELECT id, username, ssh_key, bio, created_at 
-             FROM users 
-             WHERE ssh_key = ?1"
+            "SELECT u.id, u.username, u.ssh_key, u.bio, u.created_at, u.is_admin, u.suspended_at,
+                    u.last_seen_at, u.display_name, u.links, u.pronouns, u.timezone, u.avatar,
+                    u.login_name, u.theme, COUNT(p.id)
+             FROM users u
+             LEFT JOIN posts p ON p.user_id = u.id AND p.hidden_at IS NULL
+             WHERE ?1 IS NULL OR instr(lower(u.username), lower(?1)) > 0
+             GROUP BY u.id
+             ORDER BY u.username COLLATE NOCASE"
+        )?;
+
+        let rows = stmt.query_map(params![search], |row| {
+            Ok(UserSummary {
+                user: Database::user_from_row(row)?,
+                post_count: row.get(15)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for summary in rows {
+            result.push(summary?);
+        }
+        Ok(result)
+    }
+
+    pub fn find_by_id(&self, user_id: i64) -> Result<Option<User>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!("{} WHERE id = ?1", USER_SELECT))?;
+
+        let mut rows = stmt.query(params![user_id])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(Database::user_from_row(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Check the editable profile fields against the limits on `User`
+    pub fn validate_profile(user: &User) -> Result<()> {
+        if let Some(name) = &user.display_name
+            && name.chars().count() > User::DISPLAY_NAME_MAX_CHARS
+        {
+            return Err(Error::msg(format!(
+                "Display names can be at most {} characters",
+                User::DISPLAY_NAME_MAX_CHARS
+            )));
+        }
+        if let Some(bio) = &user.bio
+            && bio.chars().count() > User::BIO_MAX_CHARS
+        {
+            return Err(Error::msg(format!("Bios can be at most {} characters", User::BIO_MAX_CHARS)));
+        }
+        if let Some(pronouns) = &user.pronouns
+            && pronouns.chars().count() > User::PRONOUNS_MAX_CHARS
+        {
+            return Err(Error::msg(format!(
+                "Pronouns can be at most {} characters",
+                User::PRONOUNS_MAX_CHARS
+            )));
+        }
+
+        let single_line = [&user.display_name, &user.bio, &user.pronouns];
+        if single_line.iter().any(|field| field.as_deref().is_some_and(|f| f.contains(char::is_control))) {
+            return Err(Error::msg("Profile fields cannot contain control characters"));
+        }
+
+        if user.links.len() > User::MAX_LINKS {
+            return Err(Error::msg(format!("A profile can have at most {} links", User::MAX_LINKS)));
+        }
+        for link in &user.links {
+            if !(link.starts_with("https://") || link.starts_with("http://")) || link.contains(char::is_whitespace) {
+                return Err(Error::msg(format!("'{}' is not an http(s) link", link)));
+            }
+            if link.len() > User::LINK_MAX_CHARS {
+                return Err(Error::msg(format!("Links can be at most {} characters", User::LINK_MAX_CHARS)));
+            }
+        }
+
+        if let Some(timezone) = &user.timezone
+            && timezone.parse::<Tz>().is_err()
+        {
+            return Err(Error::msg(format!(
+                "Unknown time zone '{}'; use a name like Europe/Berlin or America/New_York",
+                timezone
+            )));
+        }
+
+        if let Some(avatar) = &user.avatar {
+            if avatar.lines().count() > User::AVATAR_MAX_LINES {
+                return Err(Error::msg(format!(
+                    "Avatars can be at most {} lines tall",
+                    User::AVATAR_MAX_LINES
+                )));
+            }
+            if avatar.lines().any(|line| line.len() > User::AVATAR_MAX_WIDTH) {
+                return Err(Error::msg(format!(
+                    "Avatar lines can be at most {} characters wide",
+                    User::AVATAR_MAX_WIDTH
+                )));
+            }
+            // Plain printable ASCII keeps the art aligned in every terminal
+            if !avatar.chars().all(|c| c == '\n' || (' '..='~').contains(&c)) {
+                return Err(Error::msg("Avatars can only use printable ASCII characters"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Save display name, bio, links, pronouns, time zone, avatar and theme
+    pub fn update_profile(&self, user: &User) -> Result<()> {
+        Self::validate_profile(user)?;
+        let user_id = user.id.ok_or_else(|| Error::msg("User has not been saved"))?;
+
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let links = if user.links.is_empty() { None } else { Some(user.links.join("\n")) };
+        conn.execute(
+            "UPDATE users
+             SET display_name = ?1, bio = ?2, links = ?3, pronouns = ?4, timezone = ?5, avatar = ?6,
+                 theme = ?7
+             WHERE id = ?8",
+            params![
+                user.display_name,
+                user.bio,
+                links,
+                user.pronouns,
+                user.timezone,
+                user.avatar,
+                user.theme.as_str(),
+                user_id
+            ],
         )?;
+        Ok(())
+    }
+
+    /// Grant or revoke access to the moderation queue
+    pub fn find_by_ssh_key(&self, ssh_key: &str) -> Result<Option<User>> {
+        let conn_arc = self.db.get_connection();
+        let conn = conn_arc.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!("{} WHERE ssh_key = ?1", USER_SELECT))?;
 
         let mut rows = stmt.query(params![ssh_key])?;
         if let Some(row) = rows.next()? {
-            let user = User {
-                id: Some(row.get(0)?),
-                username: row.get(1)?,
-                ssh_key: row.get(2)?,
-                bio: row.get(3)?,
-                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
-                    .unwrap()
-                    .with_timezone(&chrono::Utc),
-            };
-            Ok(Some(user))
+            Ok(Some(Database::user_from_row(row)?))
         } else {
             Ok(None)
         }
@@ -139,6 +362,7 @@ impl UserManager {
     }
 
     /// Extract SSH key from SSH agent or environment
+    #[allow(clippy::collapsible_if)]
     pub fn get_client_ssh_key(&self) -> Result<String> {
         // Method 1: Try to get from SSH_ORIGINAL_COMMAND if it contains key info
         if let Ok(original_command) = env::var("SSH_ORIGINAL_COMMAND") {
@@ -187,6 +411,7 @@ impl UserManager {
     }
 
     /// Authenticate user based on SSH connection
+    #[allow(clippy::collapsible_if)]
     pub fn authenticate_from_ssh(&self) -> Result<User> {
         // Method 1: Try to authenticate by SSH key
         if let Ok(ssh_key) = self.get_client_ssh_key() {
@@ -195,10 +420,10 @@ impl UserManager {
             }
         }
 
-        // Method 2: Try to authenticate by username + verify against authorized_keys
+        // Method 2: Try to authenticate by login name + verify against authorized_keys
         let username = self.get_current_username()?;
         
-        if let Some(user) = self.find_by_username(&username)? {
+        if let Some(user) = self.find_by_login_name(&username)? {
             // If we have the user in DB, try to verify their key
             if let Ok(client_key) = self.get_client_ssh_key() {
                 if self.verify_ssh_key(&username, &client_key)? {
@@ -218,8 +443,8 @@ impl UserManager {
         )))
     }
 
-    pub fn verify_ssh_key(&self, username: &str, presented_key: &str) -> Result<bool> {
-        if let Some(user) = self.find_by_username(username)? {
+    pub fn verify_ssh_key(&self, login_name: &str, presented_key: &str) -> Result<bool> {
+        if let Some(user) = self.find_by_login_name(login_name)? {
             // Compare the actual key content (normalize whitespace)
             let stored_key = user.ssh_key.trim().replace('\n', " ");
             let presented_key = presented_key.trim().replace('\n', " ");
@@ -259,48 +484,44 @@ impl UserManager {
     }
 
     /// Main authentication method with multiple fallbacks
-    pub fn authenticate_with_fallback(&self) -> Result<User> {
+    #[allow(clippy::collapsible_if)]
+    pub fn authenticate_with_fallback(&self, session: &mut impl SessionIo) -> Result<User> {
         // First try the SSH-based authentication
         match self.authenticate_from_ssh() {
             Ok(user) => return Ok(user),
             Err(e) => {
-                eprintln!("SSH authentication failed: {}", e);
+                writeln!(session, "{}", theme::warning(format_args!("SSH authentication failed: {}", e)))?;
                 // Continue to fallbacks
             }
         }
 
-        // Fallback 1: Try username-based authentication
+        // Fallback 1: Try login-name-based authentication
         if let Ok(username) = self.get_current_username() {
-            if let Some(user) = self.find_by_username(&username)? {
+            if let Some(user) = self.find_by_login_name(&username)? {
                 return Ok(user);
             }
         }
 
         // Fallback 2: Interactive registration prompt
-        println!("No existing user found. Would you like to register? (y/n)");
-        print!("> ");
-        io::stdout().flush()?;
-        
-        let mut response = String::new();
-        io::stdin().read_line(&mut response)?;
-        
-        if response.trim().eq_ignore_ascii_case("y") {
-            return self.register_user_with_ssh();
+        let response = Self::ask(session, "No existing user found. Would you like to register? (y/n)")?;
+        if response.eq_ignore_ascii_case("y") {
+            return self.register_user_with_ssh(session);
         }
 
         Err(Error::msg("Authentication failed. Please register first or contact admin."))
     }
 
     /// Development/testing method to authenticate with minimal verification
-    pub fn authenticate_dev_mode(&self) -> Result<User> {
+    #[allow(clippy::collapsible_if)]
+    pub fn authenticate_dev_mode(&self, session: &mut impl SessionIo) -> Result<User> {
         if let Ok(username) = self.get_current_username() {
-            if let Some(user) = self.find_by_username(&username)? {
-                println!("🔓 Development mode: Authenticated as {}", username);
+            if let Some(user) = self.find_by_login_name(&username)? {
+                writeln!(session, "{} Development mode: Authenticated as {}", Icon::Unlocked, username)?;
                 return Ok(user);
             }
         }
         
-        println!("🔧 Development mode: Creating temporary user");
+        writeln!(session, "{} Development mode: Creating temporary user", Icon::Tool)?;
         let username = self.get_current_username().unwrap_or_else(|_| "dev_user".to_string());
         let mut user = User::new(
             username.clone(),
@@ -0,0 +1,205 @@
+// src/watcher.rs
+
+use crate::database::{Database, VISIBLE_POSTS_FILTER};
+use crate::ranking::RankingManager;
+use crate::theme::Icon;
+use rusqlite::{params, Connection};
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How often the watcher looks at the database
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Granularity of the sleep, so a stopped watcher exits promptly
+const TICK: Duration = Duration::from_millis(250);
+
+/// Activity noticed since the banner was last shown
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiveUpdates {
+    pub new_posts: i64,
+    pub reshares_of_mine: i64,
+    pub warnings: i64,
+    pub notifications: i64,
+}
+
+impl LiveUpdates {
+    fn is_empty(&self) -> bool {
+        *self == LiveUpdates::default()
+    }
+
+    /// One-line summary such as "2 new posts · 1 reblog or quote of your posts"
+    pub fn banner(&self) -> String {
+        let mut parts = Vec::new();
+        if self.new_posts > 0 {
+            parts.push(plural(self.new_posts, "new post", "new posts"));
+        }
+        if self.reshares_of_mine > 0 {
+            parts.push(plural(
+                self.reshares_of_mine,
+                "reblog or quote of your posts",
+                "reblogs or quotes of your posts",
+            ));
+        }
+        if self.warnings > 0 {
+            parts.push(plural(self.warnings, "message from the moderators", "messages from the moderators"));
+        }
+        if self.notifications > 0 {
+            parts.push(plural(self.notifications, "new notification", "new notifications"));
+        }
+        parts.join(&format!(" {} ", Icon::Separator))
+    }
+}
+
+fn plural(count: i64, singular: &str, plural: &str) -> String {
+    format!("{} {}", count, if count == 1 { singular } else { plural })
+}
+
+/// High-water marks: everything at or below these IDs has been accounted for
+#[derive(Clone, Copy)]
+struct Cursor {
+    data_version: Option<i64>,
+    last_post_id: i64,
+    last_warning_id: i64,
+    last_notification_id: i64,
+}
+
+/// Polls the shared SQLite file for activity relevant to one user. Other SSH
+/// sessions are separate processes, so changes are detected through
+/// `PRAGMA data_version` (bumped by commits from other connections) and ID
+/// high-water marks rather than in-process channels.
+pub struct Watcher {
+    pending: Arc<Mutex<LiveUpdates>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Watcher {
+    pub fn start(db: Database, user_id: i64) -> Result<Self> {
+        let conn_arc = db.get_connection();
+        let mut cursor = {
+            let conn = conn_arc.lock().unwrap();
+            Cursor {
+                data_version: None,
+                last_post_id: conn.query_row("SELECT COALESCE(MAX(id), 0) FROM posts", [], |row| row.get(0))?,
+                last_warning_id: conn.query_row(
+                    "SELECT COALESCE(MAX(id), 0) FROM user_warnings",
+                    [],
+                    |row| row.get(0),
+                )?,
+                last_notification_id: conn.query_row(
+                    "SELECT COALESCE(MAX(id), 0) FROM notifications",
+                    [],
+                    |row| row.get(0),
+                )?,
+            }
+        };
+
+        let pending = Arc::new(Mutex::new(LiveUpdates::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_pending = pending.clone();
+        let thread_stop = stop.clone();
+        thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                let mut waited = Duration::ZERO;
+                while waited < POLL_INTERVAL && !thread_stop.load(Ordering::Relaxed) {
+                    thread::sleep(TICK);
+                    waited += TICK;
+                }
+
+                // Popularity scores are refreshed by whichever session gets there
+                // first. The lock is released before polling, so the session gets
+                // a turn in between.
+                let _ = RankingManager::refresh_if_due(&conn_arc.lock().unwrap());
+                let conn = conn_arc.lock().unwrap();
+                // A failed poll is retried on the next interval
+                if let Ok(updates) = poll(&conn, user_id, &mut cursor) {
+                    drop(conn);
+                    let mut pending = thread_pending.lock().unwrap();
+                    pending.new_posts += updates.new_posts;
+                    pending.reshares_of_mine += updates.reshares_of_mine;
+                    pending.warnings += updates.warnings;
+                    pending.notifications += updates.notifications;
+                }
+            }
+        });
+
+        Ok(Self { pending, stop })
+    }
+
+    /// Everything noticed since the last call, or None if nothing happened
+    pub fn take_updates(&self) -> Option<LiveUpdates> {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut *pending))
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn poll(conn: &Connection, user_id: i64, cursor: &mut Cursor) -> Result<LiveUpdates> {
+    let data_version: i64 = conn.query_row("PRAGMA data_version", [], |row| row.get(0))?;
+    if cursor.data_version == Some(data_version) {
+        return Ok(LiveUpdates::default());
+    }
+    // Marks only move once every query has succeeded, so a failed poll
+    // leaves the same changes to be found on the next one
+    let mut next = Cursor { data_version: Some(data_version), ..*cursor };
+
+    let mut updates = LiveUpdates::default();
+
+    // Fix the upper bound first so posts committed mid-poll are seen next time
+    let max_post_id: i64 = conn.query_row("SELECT COALESCE(MAX(id), 0) FROM posts", [], |row| row.get(0))?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT p.id, p.kind, o.user_id
+         FROM posts p
+         LEFT JOIN posts o ON o.id = p.original_post_id
+         WHERE p.id > ?2 AND p.id <= ?3 AND p.user_id != ?1 AND {}
+         ORDER BY p.id",
+        VISIBLE_POSTS_FILTER
+    ))?;
+    let mut rows = stmt.query(params![user_id, next.last_post_id, max_post_id])?;
+    while let Some(row) = rows.next()? {
+        let original_author: Option<i64> = row.get(2)?;
+        if original_author == Some(user_id) {
+            updates.reshares_of_mine += 1;
+        } else if row.get::<_, String>(1)? != "reblog" {
+            updates.new_posts += 1;
+        }
+    }
+    // Our own posts and hidden ones still move the mark forward
+    next.last_post_id = max_post_id;
+
+    let (warnings, last_warning_id): (i64, Option<i64>) = conn.query_row(
+        "SELECT COUNT(*), MAX(id) FROM user_warnings
+         WHERE user_id = ?1 AND id > ?2 AND seen_at IS NULL",
+        params![user_id, next.last_warning_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    updates.warnings = warnings;
+    if let Some(id) = last_warning_id {
+        next.last_warning_id = id;
+    }
+
+    let (notifications, last_notification_id): (i64, Option<i64>) = conn.query_row(
+        "SELECT COUNT(*), MAX(id) FROM notifications
+         WHERE user_id = ?1 AND id > ?2 AND read_at IS NULL",
+        params![user_id, next.last_notification_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    updates.notifications = notifications;
+    if let Some(id) = last_notification_id {
+        next.last_notification_id = id;
+    }
+
+    *cursor = next;
+    Ok(updates)
+}
@@ -0,0 +1,86 @@
+// tests/cli.rs
+//
+// The numbered menu driven end to end through in-memory streams, as any
+// host that isn't the process's own terminal would drive it.
+
+use app::account::AccountManager;
+use app::activity::ActivityManager;
+use app::block::BlockManager;
+use app::bookmark::BookmarkManager;
+use app::cli::CLI;
+use app::comment::CommentManager;
+use app::database::Database;
+use app::group::GroupManager;
+use app::models::{PageRequest, User};
+use app::moderation::ModerationManager;
+use app::notification::NotificationManager;
+use app::poll::PollManager;
+use app::post::PostManager;
+use app::ranking::RankingManager;
+use app::related::RelatedManager;
+use app::saved_search::SavedSearchManager;
+use app::search::SearchManager;
+use app::session::Streams;
+use app::user::UserManager;
+use std::io::Cursor;
+
+fn cli<'a>(db: &Database, input: &'a [u8]) -> CLI<Streams<Cursor<&'a [u8]>, Vec<u8>>> {
+    CLI::new(
+        Streams::new(Cursor::new(input), Vec::new()),
+        UserManager::new(db.clone()),
+        PostManager::new(db.clone()),
+        BookmarkManager::new(db.clone()),
+        BlockManager::new(db.clone()),
+        ModerationManager::new(db.clone()),
+        ActivityManager::new(db.clone()),
+        PollManager::new(db.clone()),
+        GroupManager::new(db.clone()),
+        CommentManager::new(db.clone()),
+        NotificationManager::new(db.clone()),
+        AccountManager::new(db.clone()),
+        SearchManager::new(db.clone()),
+        SavedSearchManager::new(db.clone()),
+        RankingManager::new(db.clone()),
+        RelatedManager::new(db.clone()),
+    )
+}
+
+#[test]
+fn menu_writes_a_post_and_lists_it() {
+    let db = Database::new(":memory:").expect("in-memory database");
+    let mut alice = User::new("alice".to_string(), "ssh-ed25519 AAAA alice".to_string(), None);
+    UserManager::new(db.clone()).create_user_direct(&mut alice).unwrap();
+
+    let input = b"1\nHello from a test\nWritten through a Streams session.\n.\nn\n3\n\n16\n";
+    let mut cli = cli(&db, input);
+    cli.run_main_loop(alice.clone()).unwrap();
+    let output = String::from_utf8(cli.into_session().output).unwrap();
+
+    assert!(output.contains("1. Create new post"), "{}", output);
+    assert!(output.contains("Post 'Hello from a test' created successfully!"), "{}", output);
+    assert!(output.contains("All Posts"), "{}", output);
+    assert!(output.contains("Author: alice"), "{}", output);
+    assert!(output.contains("Written through a Streams session."), "{}", output);
+    assert!(output.contains("Goodbye!"), "{}", output);
+    // Not a terminal, so nothing but plain text
+    assert!(!output.contains('\x1b'), "{}", output);
+
+    let posts = PostManager::new(db.clone())
+        .get_user_posts(alice.id.unwrap(), PageRequest::First, 10)
+        .unwrap();
+    assert_eq!(posts.items.len(), 1);
+    assert_eq!(posts.items[0].content, "Written through a Streams session.");
+}
+
+#[test]
+fn menu_ends_when_input_runs_out() {
+    let db = Database::new(":memory:").expect("in-memory database");
+    let mut bob = User::new("bob".to_string(), "ssh-ed25519 AAAA bob".to_string(), None);
+    UserManager::new(db.clone()).create_user_direct(&mut bob).unwrap();
+
+    let mut cli = cli(&db, b"");
+    cli.run_main_loop(bob).unwrap();
+    let output = String::from_utf8(cli.into_session().output).unwrap();
+
+    assert!(output.ends_with("Choose an option (1-16): "), "{}", output);
+}